@@ -1,29 +1,343 @@
-use crate::inner::IoBufRead;
-use crate::inner::err::UbjError;
-use crate::inner::reader::UbjReader;
+use crate::inner::{IoBufRead, IoWrite};
+use crate::inner::err::{UbjError, UbjResult};
+use crate::inner::markers::UbjMarker;
+use crate::inner::reader::{IoRead, SliceRead, UbjContainerHeader, UbjRead, UbjReader};
+use crate::inner::value::{HighPrecisionAccess, RAW_VALUE_TOKEN, VALUE_TOKEN, Value};
+use crate::inner::writer::UbjWriter;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::str::FromStr;
 use serde::de::Visitor;
 
-struct UbjDeserializer<R>
+/// A deserializer of Universal Binary JSON values, reading from either a buffering reader or a
+/// slice of bytes.
+///
+/// Use it directly (rather than the `from_*` functions) to decide what to do with the input left
+/// after a value: either make sure there's nothing left by calling [`end()`](Self::end), or take
+/// the remaining input back by calling `into_inner()`.
+///
+/// #Example
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_ubj::UbjDeserializer;
+///
+/// //           [i]   [7]   [S]   [u]   [2]   [h]   [i]
+/// let bytes = [0x69, 0x07, 0x53, 0x55, 0x02, 0x68, 0x69];
+/// let mut deserializer = UbjDeserializer::from_slice(&bytes);
+/// let value = i8::deserialize(&mut deserializer).unwrap();
+/// assert_eq!(value, 7);
+/// assert_eq!(deserializer.into_inner(), &bytes[2..]);
+/// ```
+pub struct UbjDeserializer<R> {
+    ubj_reader: UbjReader<R>,
+}
+
+impl<R> UbjDeserializer<IoRead<R>>
 where
     R: IoBufRead,
 {
-    ubj_reader: UbjReader<R>,
+    /// Creates a new deserializer reading from the given buffering reader.
+    pub fn from_buf_reader(reader: R) -> Self {
+        Self::new(IoRead::new(reader))
+    }
+
+    /// Unwraps the underlying reader, whose buffer still holds the bytes not consumed yet.
+    pub fn into_inner(self) -> R {
+        self.ubj_reader.into_inner().into_inner()
+    }
 }
 
-impl<R> UbjDeserializer<R>
+impl<'de> UbjDeserializer<SliceRead<'de>> {
+    /// Creates a new deserializer reading from the given slice, which values may borrow from.
+    pub fn from_slice(slice: &'de [u8]) -> Self {
+        Self::new(SliceRead::new(slice))
+    }
+
+    /// Returns the bytes of the slice not consumed yet.
+    pub fn into_inner(self) -> &'de [u8] {
+        self.ubj_reader.into_inner().into_inner()
+    }
+}
+
+impl<'de, R> UbjDeserializer<R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
     fn new(reader: R) -> Self {
         Self {
             ubj_reader: UbjReader::new(reader),
         }
     }
+
+    /// Turns this deserializer into an iterator over a stream of values of type `T`, written
+    /// back-to-back (and possibly separated by `N` no-op markers).
+    ///
+    /// #Example
+    /// ```rust
+    /// //           [i]   [7]   [N]   [i]   [8]
+    /// let bytes = [0x69, 0x07, 0x4E, 0x69, 0x08];
+    /// let mut stream = serde_ubj::Deserializer::from_slice(&bytes).into_iter::<i8>();
+    /// assert_eq!(stream.next().unwrap().unwrap(), 7);
+    /// assert_eq!(stream.next().unwrap().unwrap(), 8);
+    /// assert_eq!(stream.value_offset(), 3);
+    /// assert!(stream.next().is_none());
+    /// ```
+    // Named after the same method of serde_json, rather than implementing IntoIterator which
+    // cannot tell the type of values to be iterated over
+    #[allow(clippy::should_implement_trait)]
+    pub fn into_iter<T>(self) -> StreamDeserializer<'de, R, T>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        StreamDeserializer {
+            deserializer: self,
+            value_offset: 0,
+            failed: false,
+            output: PhantomData,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Turns this deserializer into a lazy iterator over the elements of type `T` of an array,
+    /// which get read one at a time (so that memory stays bounded by one element).
+    ///
+    /// #Example
+    /// ```rust
+    /// //           [[]   [i]   [7]   [i]   [8]   []]
+    /// let bytes = [0x5B, 0x69, 0x07, 0x69, 0x08, 0x5D];
+    /// let mut elements = serde_ubj::Deserializer::from_slice(&bytes).into_array_iter::<i8>();
+    /// assert_eq!(elements.next().unwrap().unwrap(), 7);
+    /// assert_eq!(elements.next().unwrap().unwrap(), 8);
+    /// assert!(elements.next().is_none());
+    /// ```
+    pub fn into_array_iter<T>(self) -> ArrayIter<'de, R, T>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        ArrayIter {
+            deserializer: self,
+            header: None,
+            remaining: None,
+            done: false,
+            output: PhantomData,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Looks up the value at the given JSON Pointer (such as `/a/0/b`) and deserializes it into
+    /// a value of type `T`, whereas all values before it get skipped without being decoded.
+    /// Returns `None` if there is no value at that pointer.
+    ///
+    /// Note that the input after the value found is left unread.
+    ///
+    /// #Example
+    /// ```rust
+    /// use serde_ubj::{ubj, Deserializer};
+    ///
+    /// let bytes = serde_ubj::to_vec(&ubj!({"a": [{"b": "skipped"}, {"b": "found"}]})).unwrap();
+    /// let found: Option<&str> = Deserializer::from_slice(&bytes).pointer("/a/1/b").unwrap();
+    /// assert_eq!(found, Some("found"));
+    /// ```
+    pub fn pointer<T>(&mut self, pointer: &str) -> UbjResult<Option<T>>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        if !self.ubj_reader.find_pointer(pointer)? {
+            return Ok(None);
+        }
+        T::deserialize(&mut *self).map(Some)
+    }
+
+    /// Looks up the value at the given JSON Pointer (such as `/a/0/b`) and returns the exact bytes
+    /// it is made of, whereas all values before it get skipped without being decoded. Returns
+    /// `None` if there is no value at that pointer.
+    ///
+    /// The bytes returned make a value on their own, even when it is an element (or value) of an
+    /// optimized container whose `$` type it does not carry. Note that the input after the value
+    /// found is left unread.
+    pub fn pointer_raw(&mut self, pointer: &str) -> UbjResult<Option<Vec<u8>>> {
+        if !self.ubj_reader.find_pointer(pointer)? {
+            return Ok(None);
+        }
+        self.ubj_reader.read_raw_value().map(Some)
+    }
+
+    /// Reads the next value while applying the given JSON Merge Patch (as defined by RFC 7396) to
+    /// it, and writes the result to the given IO writer, without building the whole value in
+    /// memory.
+    ///
+    /// Values not touched by the patch get copied as they are, without being decoded, whereas the
+    /// objects the patch gets merged into are written in the plain (non-optimized) format.
+    ///
+    /// #Example
+    /// ```rust
+    /// use serde_ubj::{ubj, Deserializer, Value};
+    ///
+    /// let bytes = serde_ubj::to_vec(&ubj!({"name": "x", "config": {"level": 1}})).unwrap();
+    /// let mut patched = Vec::new();
+    /// Deserializer::from_slice(&bytes)
+    ///     .merge_patch_to(&ubj!({"config": {"level": 2}}), &mut patched)
+    ///     .unwrap();
+    /// let value: Value = serde_ubj::from_slice(&patched).unwrap();
+    /// assert_eq!(value, ubj!({"name": "x", "config": {"level": 2}}));
+    /// ```
+    pub fn merge_patch_to<W>(&mut self, patch: &Value, writer: &mut W) -> UbjResult<()>
+    where
+        W: IoWrite,
+    {
+        let mut ubj_writer = UbjWriter::new(writer);
+        self.ubj_reader.merge_patch_to(patch, &mut ubj_writer)?;
+        ubj_writer.flush()
+    }
+
+    /// Reads the next value of both this and the given target deserializer, and gives the JSON
+    /// Merge Patch (as defined by RFC 7396) which turns the former into the latter, just like
+    /// [`Value::merge_diff`] would.
+    ///
+    /// As long as two objects list the same keys in the same order, their values get compared one
+    /// entry at a time (by their bytes first), so that only the entries which differ get decoded.
+    /// All entries after the first mismatching key get decoded instead.
+    pub fn merge_diff<'t, T>(&mut self, target: &mut UbjDeserializer<T>) -> UbjResult<Value>
+    where
+        T: UbjRead<'t>,
+    {
+        self.ubj_reader.merge_diff(&mut target.ubj_reader)
+    }
+
+    /// Makes sure the whole input has been consumed, failing otherwise.
+    ///
+    /// Trailing no-op markers (`N`) are allowed, as they are just padding.
+    pub fn end(&mut self) -> UbjResult<()> {
+        self.ubj_reader.read_end()
+    }
+
+    fn next_is_high_precision(&mut self) -> UbjResult<bool> {
+        let marker = self.ubj_reader.peek_marker()?;
+        Ok(marker == UbjMarker::HighPrecision as u8)
+    }
+
+    /// Reads an integer of any width, or a high-precision number, whose value must fit `T`.
+    fn read_integer<T>(&mut self, ty: &'static str) -> UbjResult<T>
+    where
+        T: TryFrom<i64> + FromStr,
+    {
+        if self.next_is_high_precision()? {
+            let text = self.ubj_reader.read_high_precision()?;
+            if !is_json_number(&text) {
+                return Err(UbjError::InvalidHighPrecision(ty));
+            }
+            // An integer which cannot be parsed (such as a negative one for unsigned types) does
+            // not fit T, whereas fractions and exponents are not integers at all
+            text.parse::<T>().map_err(|_| {
+                let digits = text.strip_prefix('-').unwrap_or(&text);
+                if digits.bytes().all(|b| b.is_ascii_digit()) {
+                    UbjError::OutOfRange(ty)
+                } else {
+                    UbjError::InvalidHighPrecision(ty)
+                }
+            })
+        } else {
+            let n = self.ubj_reader.read_integer()?;
+            T::try_from(n).map_err(|_| UbjError::OutOfRange(ty))
+        }
+    }
+
+    /// Reads a floating-point number by means of the given function, or a high-precision number.
+    fn read_float<T>(
+        &mut self,
+        ty: &'static str,
+        read: fn(&mut UbjReader<R>) -> UbjResult<T>,
+    ) -> UbjResult<T>
+    where
+        T: FromStr,
+    {
+        if self.next_is_high_precision()? {
+            let text = self.ubj_reader.read_high_precision()?;
+            // Rust would parse texts such as "inf" or "NaN" too, which are no JSON numbers
+            if !is_json_number(&text) {
+                return Err(UbjError::InvalidHighPrecision(ty));
+            }
+            text.parse::<T>()
+                .map_err(|_| UbjError::InvalidHighPrecision(ty))
+        } else {
+            read(&mut self.ubj_reader)
+        }
+    }
+
+    fn read_str(&mut self) -> UbjResult<Cow<'de, str>> {
+        // Other producers may well write bigints (and big decimals) as high-precision numbers,
+        // whose text can be taken as is when the target asks for a string
+        if self.next_is_high_precision()? {
+            self.ubj_reader.read_high_precision().map(Cow::Owned)
+        } else {
+            self.ubj_reader.read_marked_str()
+        }
+    }
+}
+
+/// Tells whether the given text is a number as defined by the JSON grammar, which the decimal text
+/// of high-precision numbers must follow.
+pub(crate) fn is_json_number(text: &str) -> bool {
+    fn digits(bytes: &[u8]) -> usize {
+        bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+    let bytes = text.as_bytes();
+    let mut i = usize::from(bytes.first() == Some(&b'-'));
+
+    // The integer part, with no leading zeros
+    match digits(&bytes[i..]) {
+        0 => return false,
+        n if n > 1 && bytes[i] == b'0' => return false,
+        n => i += n,
+    }
+    // The optional fraction part
+    if bytes.get(i) == Some(&b'.') {
+        match digits(&bytes[i + 1..]) {
+            0 => return false,
+            n => i += 1 + n,
+        }
+    }
+    // The optional exponent part
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        match digits(&bytes[i..]) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+    i == bytes.len()
+}
+
+/// Visits the decimal text of a high-precision number, as the narrowest Rust integer it fits or
+/// otherwise (such as for big decimals) as text, not to lose any precision.
+pub(crate) fn visit_high_precision<'de, V>(text: String, visitor: V) -> UbjResult<V::Value>
+where
+    V: Visitor<'de>,
+{
+    if !is_json_number(&text) {
+        return Err(UbjError::InvalidHighPrecision("any"));
+    }
+    if let Ok(v) = text.parse::<i64>() {
+        visitor.visit_i64(v)
+    } else if let Ok(v) = text.parse::<u64>() {
+        visitor.visit_u64(v)
+    } else if let Ok(v) = text.parse::<i128>() {
+        visitor.visit_i128(v)
+    } else if let Ok(v) = text.parse::<u128>() {
+        visitor.visit_u128(v)
+    } else {
+        visitor.visit_string(text)
+    }
 }
 
 impl<'de, R> serde::Deserializer<'de> for &mut UbjDeserializer<R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
     type Error = UbjError;
 
@@ -31,11 +345,33 @@ where
     // S C A L A R    values
     // ---------------------------------------------------------------------------------
 
-    fn deserialize_any<V>(self, _isitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!("deserialize_any() is not yet implemented.")
+        // Universal Binary JSON is self-describing, so the marker tells what comes next
+        self.ubj_reader.skip_no_ops()?;
+        let marker = self.ubj_reader.peek_marker()?;
+        match UbjMarker::from_u8(marker) {
+            Some(UbjMarker::Null) => self.deserialize_unit(visitor),
+            Some(UbjMarker::True | UbjMarker::False) => self.deserialize_bool(visitor),
+            Some(UbjMarker::Int8) => self.deserialize_i8(visitor),
+            Some(UbjMarker::Uint8) => self.deserialize_u8(visitor),
+            Some(UbjMarker::Int16) => self.deserialize_i16(visitor),
+            Some(UbjMarker::Int32) => self.deserialize_i32(visitor),
+            Some(UbjMarker::Int64) => self.deserialize_i64(visitor),
+            Some(UbjMarker::Float32) => self.deserialize_f32(visitor),
+            Some(UbjMarker::Float64) => self.deserialize_f64(visitor),
+            Some(UbjMarker::Char) => self.deserialize_char(visitor),
+            Some(UbjMarker::String) => self.deserialize_string(visitor),
+            Some(UbjMarker::HighPrecision) => {
+                let text = self.ubj_reader.read_high_precision()?;
+                visit_high_precision(text, visitor)
+            }
+            Some(UbjMarker::StartArray) => self.deserialize_seq(visitor),
+            Some(UbjMarker::StartObject) => self.deserialize_map(visitor),
+            _ => Err(UbjError::UnexpectedMarker(marker)),
+        }
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -60,8 +396,7 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_int8()
+        self.read_integer("i8")
             .and_then(|v| visitor.visit_i8(v))
     }
 
@@ -69,8 +404,7 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_uint8()
+        self.read_integer("u8")
             .and_then(|v| visitor.visit_u8(v))
     }
 
@@ -78,8 +412,7 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_int16()
+        self.read_integer("i16")
             .and_then(|v| visitor.visit_i16(v))
     }
 
@@ -87,8 +420,7 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_int32()
+        self.read_integer("i32")
             .and_then(|v| visitor.visit_i32(v))
     }
 
@@ -96,52 +428,55 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_int64()
+        self.read_integer("i64")
             .and_then(|v| visitor.visit_i64(v))
     }
 
-    fn deserialize_i128<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(UbjError::Unsupported("i128"))
+        self.read_integer("i128")
+            .and_then(|v| visitor.visit_i128(v))
     }
 
-    fn deserialize_u16<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        Err(UbjError::Unsupported("u16"))
+        self.read_integer("u16")
+            .and_then(|v| visitor.visit_u16(v))
     }
 
-    fn deserialize_u32<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        Err(UbjError::Unsupported("u32"))
+        self.read_integer("u32")
+            .and_then(|v| visitor.visit_u32(v))
     }
 
-    fn deserialize_u64<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        Err(UbjError::Unsupported("u64"))
+        self.read_integer("u64")
+            .and_then(|v| visitor.visit_u64(v))
     }
 
-    fn deserialize_u128<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(UbjError::Unsupported("u128"))
+        self.read_integer("u128")
+            .and_then(|v| visitor.visit_u128(v))
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_float32()
+        self.read_float("f32", UbjReader::read_float32)
             .and_then(|v| visitor.visit_f32(v))
     }
 
@@ -149,8 +484,7 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_float64()
+        self.read_float("f64", UbjReader::read_float64)
             .and_then(|v| visitor.visit_f64(v))
     }
 
@@ -167,32 +501,34 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader
-            .read_marked_string()
-            .and_then(|s| visitor.visit_string(s))
+        self.deserialize_str(visitor)
     }
 
-    fn deserialize_str<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!("deserialize_str() is not yet implemented.")
-        // let str: &'de str = self.ubj_reader.read_str()
-        // visitor.visit_borrowed_str(str)
+        match self.read_str()? {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
     }
 
-    fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!("deserialize_bytes() is not yet implemented.")
+        self.deserialize_byte_buf(visitor)
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!("deserialize_byte_buf() is not yet implemented.")
+        match self.ubj_reader.read_bytes()? {
+            Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+        }
     }
 
     // ---------------------------------------------------------------------------------
@@ -214,8 +550,8 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader.read_start_array()?;
-        let seq_accessor = UbjAccessor { deserializer: self };
+        let header = self.ubj_reader.read_start_array()?;
+        let seq_accessor = UbjAccessor::new(self, header);
         visitor.visit_seq(seq_accessor)
         // NO need to read the end_array marker here
     }
@@ -225,10 +561,10 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader.read_start_array()?;
-        let seq_accessor = UbjAccessor { deserializer: self };
-        let tuple = visitor.visit_seq(seq_accessor)?;
-        self.ubj_reader.read_end_array()?;
+        let header = self.ubj_reader.read_start_array()?;
+        let mut seq_accessor = UbjAccessor::new(self, header);
+        let tuple = visitor.visit_seq(&mut seq_accessor)?;
+        seq_accessor.end_array()?;
         Ok(tuple)
     }
 
@@ -236,8 +572,10 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        let id = self.ubj_reader.read_unmarked_string()?;
-        visitor.visit_str(&id)
+        match self.ubj_reader.read_unmarked_str()? {
+            Cow::Borrowed(id) => visitor.visit_borrowed_str(id),
+            Cow::Owned(id) => visitor.visit_string(id),
+        }
     }
 
     fn deserialize_unit_struct<V>(
@@ -255,12 +593,30 @@ where
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
+        if name == VALUE_TOKEN {
+            // A high-precision number is told to the Value visitor as an enum variant, whereas
+            // anything else is visited as usual
+            if self.next_is_high_precision()? {
+                let text = self.ubj_reader.read_high_precision()?;
+                if !is_json_number(&text) {
+                    return Err(UbjError::InvalidHighPrecision("Value"));
+                }
+                return visitor.visit_enum(HighPrecisionAccess::new(text));
+            }
+            return self.deserialize_any(visitor);
+        }
+        if name == RAW_VALUE_TOKEN {
+            return match self.ubj_reader.read_raw()? {
+                Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+                Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+            };
+        }
         visitor.visit_newtype_struct(self)
     }
 
@@ -273,10 +629,10 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader.read_start_array()?;
-        let seq_accessor = UbjAccessor { deserializer: self };
-        let tuple = visitor.visit_seq(seq_accessor)?;
-        self.ubj_reader.read_end_array()?;
+        let header = self.ubj_reader.read_start_array()?;
+        let mut seq_accessor = UbjAccessor::new(self, header);
+        let tuple = visitor.visit_seq(&mut seq_accessor)?;
+        seq_accessor.end_array()?;
         Ok(tuple)
     }
 
@@ -289,8 +645,8 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader.read_start_object()?;
-        let map_accessor = UbjAccessor { deserializer: self };
+        let header = self.ubj_reader.read_start_object()?;
+        let map_accessor = UbjAccessor::new(self, header);
         let map = visitor.visit_map(map_accessor)?;
         // DO NOT self.ubj_reader.read_end_object()?;
         Ok(map)
@@ -305,8 +661,8 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader.read_start_object()?;
-        let enum_accessor = UbjAccessor { deserializer: self };
+        let header = self.ubj_reader.read_start_object()?;
+        let enum_accessor = UbjAccessor::new(self, header);
         visitor.visit_enum(enum_accessor)
     }
 
@@ -314,18 +670,19 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.ubj_reader.read_start_object()?;
-        let map_accessor = UbjAccessor { deserializer: self };
+        let header = self.ubj_reader.read_start_object()?;
+        let map_accessor = UbjAccessor::new(self, header);
         let map = visitor.visit_map(map_accessor)?;
         // DO NOT self.ubj_reader.read_end_object()
         Ok(map)
     }
 
-    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!("deserialize_ignored_any() is not yet implemented.")
+        self.ubj_reader.skip_value()?;
+        visitor.visit_unit()
     }
 }
 
@@ -333,16 +690,95 @@ where
 //  A C C E S S O R s
 // -----------------------------------------------------------------------------
 
-struct UbjAccessor<'a, R>
+struct UbjAccessor<'a, R> {
+    deserializer: &'a mut UbjDeserializer<R>,
+    header: UbjContainerHeader,
+    // The count of elements (or entries) still to be read, for optimized containers only
+    remaining: Option<usize>,
+}
+
+impl<'de, 'a, R> UbjAccessor<'a, R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
-    deserializer: &'a mut UbjDeserializer<R>,
+    fn new(deserializer: &'a mut UbjDeserializer<R>, header: UbjContainerHeader) -> Self {
+        Self {
+            deserializer,
+            header,
+            remaining: header.count,
+        }
+    }
+
+    /// Tells whether there's another element (or entry) to be read, and consumes the closing
+    /// marker of plain containers otherwise.
+    fn has_next(&mut self, read_end: fn(&mut UbjReader<R>) -> UbjResult<()>) -> UbjResult<bool> {
+        if self.remaining != Some(0) {
+            self.skip_no_ops()?;
+        }
+        Ok(match self.remaining {
+            Some(0) => false,
+            Some(n) => {
+                self.remaining = Some(n - 1);
+                true
+            }
+            None => read_end(&mut self.deserializer.ubj_reader).is_err(),
+        })
+    }
+
+    /// Skips the no-op markers ahead, which may appear anywhere but in place of the values of a
+    /// container declaring their type.
+    fn skip_no_ops(&mut self) -> UbjResult<()> {
+        if self.header.element_type.is_none() {
+            self.deserializer.ubj_reader.skip_no_ops()?;
+        }
+        Ok(())
+    }
+
+    fn deserialize_next<T>(&mut self, seed: T) -> UbjResult<T::Value>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        self.skip_no_ops()?;
+        self.deserializer
+            .ubj_reader
+            .imply_marker(self.header.element_type);
+        seed.deserialize(&mut *self.deserializer)
+    }
+
+    /// Completes the reading of a fixed-length array, which must have no elements left.
+    fn end_array(&mut self) -> UbjResult<()> {
+        match self.remaining {
+            None => {
+                self.skip_no_ops()?;
+                self.deserializer.ubj_reader.read_end_array()
+            }
+            Some(0) => Ok(()),
+            Some(_) => Err(serde::de::Error::invalid_length(
+                self.header.count.unwrap_or_default(),
+                &"fewer elements in array",
+            )),
+        }
+    }
+
+    /// Completes the reading of an object, which must have no entries left.
+    fn end_object(&mut self) -> UbjResult<()> {
+        match self.remaining {
+            None => {
+                self.skip_no_ops()?;
+                self.deserializer.ubj_reader.read_end_object()
+            }
+            Some(0) => Ok(()),
+            Some(_) => Err(serde::de::Error::invalid_length(
+                self.header.count.unwrap_or_default(),
+                &"fewer entries in object",
+            )),
+        }
+    }
 }
 
 impl<'de, 'a, R> serde::de::SeqAccess<'de> for UbjAccessor<'a, R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
     type Error = UbjError;
 
@@ -353,16 +789,21 @@ where
         // Returning None signals the visitor about the end of the sequence,
         // while returning Some signals the visitor to continue reading the sequence.
         // This strategy does apply only for variable-length sequences and not for fixed-length ones.
-        match self.deserializer.ubj_reader.read_end_array() {
-            Ok(_) => Ok(None),
-            Err(_) => seed.deserialize(&mut *self.deserializer).map(Some),
+        if self.has_next(UbjReader::read_end_array)? {
+            self.deserialize_next(seed).map(Some)
+        } else {
+            Ok(None)
         }
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.remaining
+    }
 }
 
 impl<'de, 'a, R> serde::de::MapAccess<'de> for UbjAccessor<'a, R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
     type Error = UbjError;
 
@@ -370,21 +811,21 @@ where
     where
         K: serde::de::DeserializeSeed<'de>,
     {
-        match self.deserializer.ubj_reader.read_end_object() {
-            Ok(_) => {
-                // Returning None signals the visitor about the end of the object
-                Ok(None)
-            }
-            Err(_) => {
-                // Force the use of deserialize_identifier()
-                // Instead of calling seed.deserialize(&mut *self.deserializer)
-                // we call with the IdentifierDeserializer, instead
-                let mut identifier_deserializer = UbjIdentifierDeserializer {
-                    deserializer: self.deserializer,
-                };
-                seed.deserialize(&mut identifier_deserializer).map(Some)
-                // Returning some value signals the visitor to continue reading
-            }
+        if self.has_next(UbjReader::read_end_object)? {
+            // Keys carry no marker of their own (even within a container declaring the type of
+            // its values), so no-op markers can always be told apart from them
+            self.deserializer.ubj_reader.skip_no_ops()?;
+            // Force the use of deserialize_identifier()
+            // Instead of calling seed.deserialize(&mut *self.deserializer)
+            // we call with the IdentifierDeserializer, instead
+            let mut identifier_deserializer = UbjIdentifierDeserializer {
+                deserializer: self.deserializer,
+            };
+            seed.deserialize(&mut identifier_deserializer).map(Some)
+            // Returning some value signals the visitor to continue reading
+        } else {
+            // Returning None signals the visitor about the end of the object
+            Ok(None)
         }
     }
 
@@ -392,21 +833,29 @@ where
     where
         V: serde::de::DeserializeSeed<'de>,
     {
-        seed.deserialize(&mut *self.deserializer)
+        self.deserialize_next(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.remaining
     }
 }
 
 impl<'de, 'a, R> serde::de::EnumAccess<'de> for UbjAccessor<'a, R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
     type Error = UbjError;
     type Variant = Self;
 
-    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
     where
         V: serde::de::DeserializeSeed<'de>,
     {
+        if let Some(n) = self.remaining {
+            // The variant identifier is the one and only key of the object
+            self.remaining = Some(n.saturating_sub(1));
+        }
         let identifier = seed.deserialize(&mut *self.deserializer)?;
         Ok((identifier, self))
     }
@@ -414,66 +863,69 @@ where
 
 impl<'de, 'a, R> serde::de::VariantAccess<'de> for UbjAccessor<'a, R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
     type Error = UbjError;
 
-    fn unit_variant(self) -> Result<(), Self::Error> {
+    fn unit_variant(mut self) -> Result<(), Self::Error> {
+        self.deserializer
+            .ubj_reader
+            .imply_marker(self.header.element_type);
         self.deserializer.ubj_reader.read_null()?;
-        self.deserializer.ubj_reader.read_end_object()
+        self.end_object()
     }
 
-    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    fn newtype_variant_seed<T>(mut self, seed: T) -> Result<T::Value, Self::Error>
     where
         T: serde::de::DeserializeSeed<'de>,
     {
-        let associated_data = seed.deserialize(&mut *self.deserializer)?;
-        self.deserializer.ubj_reader.read_end_object()?;
+        let associated_data = self.deserialize_next(seed)?;
+        self.end_object()?;
         Ok(associated_data)
     }
 
-    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    fn tuple_variant<V>(mut self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        self.deserializer.ubj_reader.read_start_array()?;
-        let seq_accessor = UbjAccessor {
-            deserializer: self.deserializer,
-        };
-        let tuple = visitor.visit_seq(seq_accessor)?;
-        self.deserializer.ubj_reader.read_end_array()?;
-        self.deserializer.ubj_reader.read_end_object()?;
+        self.deserializer
+            .ubj_reader
+            .imply_marker(self.header.element_type);
+        let header = self.deserializer.ubj_reader.read_start_array()?;
+        let mut seq_accessor = UbjAccessor::new(self.deserializer, header);
+        let tuple = visitor.visit_seq(&mut seq_accessor)?;
+        seq_accessor.end_array()?;
+        self.end_object()?;
         Ok(tuple)
     }
 
     fn struct_variant<V>(
-        self,
+        mut self,
         _fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        self.deserializer.ubj_reader.read_start_object()?;
-        let map_accessor = UbjAccessor {
-            deserializer: self.deserializer,
-        };
+        self.deserializer
+            .ubj_reader
+            .imply_marker(self.header.element_type);
+        let header = self.deserializer.ubj_reader.read_start_object()?;
+        let map_accessor = UbjAccessor::new(self.deserializer, header);
         let map = visitor.visit_map(map_accessor)?;
-        self.deserializer.ubj_reader.read_end_object()?;
+        // NO need to read the end_object marker of the struct here
+        self.end_object()?;
         Ok(map)
     }
 }
 
-struct UbjIdentifierDeserializer<'a, R>
-where
-    R: IoBufRead,
-{
+struct UbjIdentifierDeserializer<'a, R> {
     deserializer: &'a mut UbjDeserializer<R>,
 }
 
 impl<'de, 'a, R> serde::de::Deserializer<'de> for &mut UbjIdentifierDeserializer<'a, R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
     type Error = UbjError;
 
@@ -492,11 +944,160 @@ where
     }
 }
 
+// -----------------------------------------------------------------------------
+//  S T R E A M s
+// -----------------------------------------------------------------------------
+
+/// An iterator over a stream of values of type `T`, written back-to-back.
+///
+/// The iteration ends cleanly when the input ends at a value boundary, whereas it yields an error
+/// (and then ends) when the input ends in the middle of a value, or when it contains anything
+/// else than a value of type `T`.
+pub struct StreamDeserializer<'de, R, T> {
+    deserializer: UbjDeserializer<R>,
+    value_offset: usize,
+    failed: bool,
+    output: PhantomData<fn() -> T>,
+    lifetime: PhantomData<&'de ()>,
+}
+
+impl<'de, R, T> StreamDeserializer<'de, R, T>
+where
+    R: UbjRead<'de>,
+    T: serde::Deserialize<'de>,
+{
+    /// Returns the byte offset of the input where the last value yielded started.
+    pub fn value_offset(&self) -> usize {
+        self.value_offset
+    }
+
+    /// Returns the number of bytes of the input consumed so far.
+    pub fn byte_offset(&self) -> usize {
+        self.deserializer.ubj_reader.bytes_consumed()
+    }
+}
+
+impl<'de, R, T> Iterator for StreamDeserializer<'de, R, T>
+where
+    R: UbjRead<'de>,
+    T: serde::Deserialize<'de>,
+{
+    type Item = UbjResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = match self.deserializer.ubj_reader.skip_no_ops() {
+            Ok(true) => return None,
+            Ok(false) => {
+                self.value_offset = self.byte_offset();
+                T::deserialize(&mut self.deserializer)
+            }
+            Err(err) => Err(err),
+        };
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+/// A lazy iterator over the elements of type `T` of an array, either plain or optimized.
+///
+/// The iteration ends after the closing `]` marker (or after the `#` count of elements) has been
+/// reached, whereas it yields an error (and then ends) when the input is not such an array.
+pub struct ArrayIter<'de, R, T> {
+    deserializer: UbjDeserializer<R>,
+    // The header of the array, once its start has been read
+    header: Option<UbjContainerHeader>,
+    remaining: Option<usize>,
+    done: bool,
+    output: PhantomData<fn() -> T>,
+    lifetime: PhantomData<&'de ()>,
+}
+
+impl<'de, R, T> ArrayIter<'de, R, T>
+where
+    R: UbjRead<'de>,
+    T: serde::Deserialize<'de>,
+{
+    /// Makes sure the whole input has been consumed after the array, failing otherwise.
+    pub fn end(&mut self) -> UbjResult<()> {
+        self.deserializer.end()
+    }
+
+    fn has_next(&mut self) -> UbjResult<bool> {
+        let header = match self.header {
+            Some(header) => header,
+            None => {
+                let header = self.deserializer.ubj_reader.read_start_array()?;
+                self.header = Some(header);
+                self.remaining = header.count;
+                header
+            }
+        };
+        if header.element_type.is_none() && self.remaining != Some(0) {
+            self.deserializer.ubj_reader.skip_no_ops()?;
+        }
+        match self.remaining {
+            Some(0) => Ok(false),
+            Some(n) => {
+                self.remaining = Some(n - 1);
+                self.deserializer
+                    .ubj_reader
+                    .imply_marker(header.element_type);
+                Ok(true)
+            }
+            None => {
+                let marker = self.deserializer.ubj_reader.peek_marker()?;
+                if marker == UbjMarker::EndArray as u8 {
+                    self.deserializer.ubj_reader.read_end_array()?;
+                    Ok(false)
+                } else {
+                    Ok(true)
+                }
+            }
+        }
+    }
+}
+
+impl<'de, R, T> Iterator for ArrayIter<'de, R, T>
+where
+    R: UbjRead<'de>,
+    T: serde::Deserialize<'de>,
+{
+    type Item = UbjResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = match self.has_next() {
+            Ok(false) => {
+                self.done = true;
+                return None;
+            }
+            Ok(true) => T::deserialize(&mut self.deserializer),
+            Err(err) => Err(err),
+        };
+        self.done = result.is_err();
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(n) if !self.done => (n, Some(n)),
+            _ => (0, None),
+        }
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 
 /// Deserialize from an IO buffering reader into a Rust value of type `T`.
 ///
-/// Be aware that, this function requires a buffered reader
+/// Be aware that, this function requires a buffered reader. It returns as soon as the value has
+/// been read, leaving whatever follows in the reader (see [`UbjDeserializer::end()`] to check
+/// there's nothing else left).
 /// #Example
 /// ```rust, ignore
 /// use core::error;
@@ -517,14 +1118,48 @@ where
     R: IoBufRead,
     T: serde::Deserialize<'de>,
 {
-    let mut deserializer = UbjDeserializer::new(reader);
+    let mut deserializer = UbjDeserializer::new(IoRead::new(reader));
     serde::Deserialize::deserialize(&mut deserializer)
 }
 
 /// Deserialize from a vector (in-memory buffer) of bytes into a Rust value of type `T`.
+///
+/// It fails if any bytes (other than no-op markers) are left over after the value.
 pub fn from_vec<'de, T>(vec: Vec<u8>) -> Result<T, UbjError>
 where
     T: serde::Deserialize<'de>,
 {
-    from_buf_reader(&mut vec.as_slice())
+    let mut deserializer = UbjDeserializer::from_buf_reader(vec.as_slice());
+    let value = serde::Deserialize::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Deserialize from a slice of bytes into a Rust value of type `T`, which may borrow strings
+/// (and bytes) straight from the slice instead of copying them.
+///
+/// It fails if any bytes (other than no-op markers) are left over after the value.
+///
+/// #Example
+/// ```rust
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Greeting<'a> {
+///     text: &'a str,
+/// }
+///
+/// //           [{]   [u]   [4]   [t]   [e]   [x]   [t]   [S]   [u]   [2]   [h]   [i]   [}]
+/// let bytes = [0x7B, 0x55, 0x04, 0x74, 0x65, 0x78, 0x74, 0x53, 0x55, 0x02, 0x68, 0x69, 0x7D];
+/// let greeting: Greeting = serde_ubj::from_slice(&bytes).unwrap();
+/// assert_eq!(greeting.text, "hi");
+/// ```
+pub fn from_slice<'de, T>(slice: &'de [u8]) -> Result<T, UbjError>
+where
+    T: serde::Deserialize<'de>,
+{
+    let mut deserializer = UbjDeserializer::from_slice(slice);
+    let value = serde::Deserialize::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
 }
@@ -21,6 +21,23 @@ pub enum UbjError {
     /// Unexpected end of file
     UnexpectedEof,
 
+    /// A negative length (or count) of a string (or container).
+    NegativeLength(i64),
+
+    /// Bytes left over after the end of the value, starting at the given offset of the input.
+    TrailingBytes(usize),
+
+    /// A value whose encoding takes a different number of bytes (the latter) than the encoding it
+    /// replaces in place (the former).
+    LengthMismatch(usize, usize),
+
+    /// A number which does not fit the range of the Rust type it is deserialized to.
+    OutOfRange(&'static str),
+
+    /// A high-precision number whose decimal text cannot be parsed into the Rust type it is
+    /// deserialized to.
+    InvalidHighPrecision(&'static str),
+
     /// Error involving the conversion of raw bytes to UTF-8 characters
     Utf8Error(core::str::Utf8Error),
 
@@ -69,6 +86,29 @@ impl core::fmt::Display for UbjError {
                 write!(f, "Unexpected end of file")
             }
 
+            UbjError::NegativeLength(n) => {
+                write!(f, "Negative length: {n}")
+            }
+
+            UbjError::TrailingBytes(offset) => {
+                write!(f, "Trailing bytes at offset: {offset}")
+            }
+
+            UbjError::LengthMismatch(expected, found) => {
+                write!(
+                    f,
+                    "Length mismatch: expected {expected} bytes, found {found}"
+                )
+            }
+
+            UbjError::OutOfRange(ty) => {
+                write!(f, "Number out of range for {ty}")
+            }
+
+            UbjError::InvalidHighPrecision(ty) => {
+                write!(f, "Invalid high-precision number for {ty}")
+            }
+
             UbjError::BufferTooSmall(capacity) => {
                 write!(
                     f,
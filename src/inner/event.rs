@@ -0,0 +1,499 @@
+use crate::inner::err::{UbjError, UbjResult};
+use crate::inner::markers::UbjMarker;
+use crate::inner::number::Number;
+use crate::inner::reader::{IoRead, SliceRead, UbjRead, UbjReader};
+use crate::inner::ser::to_writer;
+use crate::inner::writer::UbjWriter;
+use crate::inner::{IoBufRead, IoWrite};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// One event of the stream a Universal Binary JSON input is made of, which keeps all the details
+/// of its encoding.
+///
+/// Besides the markers of numbers (see [`Number`]), events keep the markers the lengths of
+/// strings are written with, the `$` type and the `#` count headers of optimized containers, and
+/// the `N` no-op markers. Therefore, writing the events read from some input by means of an
+/// [`EventWriter`] gives back the very same bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// The `N` no-op marker
+    NoOp,
+    /// The `Z` value
+    Null,
+    /// The `T` or `F` value
+    Bool(bool),
+    /// An `i`, `U`, `I`, `l`, `L`, `d` or `D` number
+    Number(Number),
+    /// A `C` character (within the ASCII range)
+    Char(char),
+    /// An `S` string
+    String(Text),
+    /// An `H` number, as its decimal text
+    HighPrecision(Text),
+    /// The start of an array
+    StartArray(ContainerHeader),
+    /// The end of an array, whether it has the closing `]` marker or not (as counted arrays do)
+    EndArray,
+    /// The start of an object
+    StartObject(ContainerHeader),
+    /// The key of an entry, whose value follows
+    Key(Text),
+    /// The end of an object, whether it has the closing `}` marker or not (as counted objects do)
+    EndObject,
+}
+
+/// A text (that is a string, a key or the decimal text of a high-precision number) along with the
+/// marker of the integer its length is written with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Text {
+    /// The text
+    pub text: String,
+    /// The `i`, `U`, `I`, `l` or `L` marker of the length
+    pub len_marker: char,
+}
+
+impl Text {
+    /// Creates a text whose length gets written with the narrowest marker it fits.
+    pub fn new<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let text = text.into();
+        let len_marker = narrowest_marker(text.len());
+        Self { text, len_marker }
+    }
+}
+
+/// The header of a container, as optionally optimized with its `$` type and its `#` count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ContainerHeader {
+    /// The marker of the type of all elements (or values), which then carry no marker.
+    pub element_type: Option<char>,
+    /// The count of elements (or entries), along with the `i`, `U`, `I`, `l` or `L` marker of the
+    /// integer it is written with.
+    pub count: Option<(usize, char)>,
+}
+
+fn narrowest_marker(len: usize) -> char {
+    if len <= u8::MAX as usize {
+        'U'
+    } else if len <= i16::MAX as usize {
+        'I'
+    } else if len <= i32::MAX as usize {
+        'l'
+    } else {
+        'L'
+    }
+}
+
+// The state of a container being read (or written)
+struct Frame {
+    object: bool,
+    element_type: Option<UbjMarker>,
+    remaining: Option<usize>,
+    // Whether the next event of an object is a key (rather than a value)
+    expecting_key: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+//  R E A D E R
+// -------------------------------------------------------------------------------------------------
+
+/// An iterator over the events of an input, which may contain several values back-to-back.
+///
+/// The iteration ends cleanly when the input ends at a value boundary, whereas it yields an error
+/// (and then ends) when the input is not valid Universal Binary JSON.
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::{Event, EventReader, EventWriter, Number};
+///
+/// //           [[]   [#]   [i]   [2]   [N]   [I]   [1]         [I]   [2]
+/// let bytes = [0x5B, 0x23, 0x69, 0x02, 0x4E, 0x49, 0x00, 0x01, 0x49, 0x00, 0x02];
+///
+/// let mut writer = EventWriter::new(Vec::new());
+/// for event in EventReader::from_slice(&bytes) {
+///     match event.unwrap() {
+///         // Rewrite the second number only, keeping everything else as it is
+///         Event::Number(n) if n.as_i64() == Some(2) => {
+///             writer.write(&Event::Number(Number::from(7_i16))).unwrap()
+///         }
+///         event => writer.write(&event).unwrap(),
+///     }
+/// }
+/// assert_eq!(writer.into_inner(), [0x5B, 0x23, 0x69, 0x02, 0x4E, 0x49, 0x00, 0x01, 0x49, 0x00, 0x07]);
+/// ```
+pub struct EventReader<R> {
+    ubj_reader: UbjReader<R>,
+    stack: Vec<Frame>,
+    failed: bool,
+}
+
+impl<R> EventReader<IoRead<R>>
+where
+    R: IoBufRead,
+{
+    /// Creates a new event reader reading from the given buffering reader.
+    pub fn from_buf_reader(reader: R) -> Self {
+        Self::new(IoRead::new(reader))
+    }
+}
+
+impl<'de> EventReader<SliceRead<'de>> {
+    /// Creates a new event reader reading from the given slice.
+    pub fn from_slice(slice: &'de [u8]) -> Self {
+        Self::new(SliceRead::new(slice))
+    }
+}
+
+impl<'de, R> EventReader<R>
+where
+    R: UbjRead<'de>,
+{
+    fn new(reader: R) -> Self {
+        Self {
+            ubj_reader: UbjReader::new(reader),
+            stack: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// Returns the number of bytes of the input consumed so far.
+    pub fn byte_offset(&self) -> usize {
+        self.ubj_reader.bytes_consumed()
+    }
+
+    /// Reads the next event, if the input has not ended at a value boundary.
+    fn read_event(&mut self) -> UbjResult<Option<Event>> {
+        let Some(frame) = self.stack.last() else {
+            if self.ubj_reader.is_at_end()? {
+                return Ok(None);
+            }
+            return self.read_value_start().map(Some);
+        };
+        let (object, element_type, remaining) = (frame.object, frame.element_type, frame.remaining);
+        let key = object && frame.expecting_key;
+
+        // Elements and entries may be preceded by no-op markers, or followed by the end
+        if !object || key {
+            if remaining == Some(0) {
+                return Ok(Some(self.end_container()));
+            }
+            if element_type.is_none() {
+                let marker = self.ubj_reader.peek_marker()?;
+                if marker == UbjMarker::NoOp as u8 {
+                    self.ubj_reader.read_marker(UbjMarker::NoOp)?;
+                    return Ok(Some(Event::NoOp));
+                }
+                let end = if object {
+                    UbjMarker::EndObject
+                } else {
+                    UbjMarker::EndArray
+                };
+                if remaining.is_none() && marker == end as u8 {
+                    self.ubj_reader.read_marker(end)?;
+                    return Ok(Some(self.end_container()));
+                }
+            }
+            if let Some(frame) = self.stack.last_mut() {
+                frame.remaining = remaining.map(|n| n - 1);
+                frame.expecting_key = false;
+            }
+        }
+        if key {
+            return self.read_text().map(|text| Some(Event::Key(text)));
+        }
+        self.ubj_reader.imply_marker(element_type);
+        self.read_value_start().map(Some)
+    }
+
+    fn end_container(&mut self) -> Event {
+        let object = self.stack.pop().is_some_and(|frame| frame.object);
+        self.end_value();
+        if object {
+            Event::EndObject
+        } else {
+            Event::EndArray
+        }
+    }
+
+    // Makes the enclosing object (if any) expect a key again
+    fn end_value(&mut self) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.expecting_key = frame.object;
+        }
+    }
+
+    fn read_value_start(&mut self) -> UbjResult<Event> {
+        let byte = self.ubj_reader.peek_marker()?;
+        let marker = UbjMarker::from_u8(byte).ok_or(UbjError::UnexpectedMarker(byte))?;
+        let event = match marker {
+            UbjMarker::NoOp if self.stack.is_empty() => {
+                self.ubj_reader.read_marker(marker)?;
+                return Ok(Event::NoOp);
+            }
+            UbjMarker::Null => self.ubj_reader.read_null().map(|_| Event::Null)?,
+            UbjMarker::True | UbjMarker::False => Event::Bool(self.ubj_reader.read_bool()?),
+            UbjMarker::Int8 => Event::Number(self.ubj_reader.read_int8()?.into()),
+            UbjMarker::Uint8 => Event::Number(self.ubj_reader.read_uint8()?.into()),
+            UbjMarker::Int16 => Event::Number(self.ubj_reader.read_int16()?.into()),
+            UbjMarker::Int32 => Event::Number(self.ubj_reader.read_int32()?.into()),
+            UbjMarker::Int64 => Event::Number(self.ubj_reader.read_int64()?.into()),
+            UbjMarker::Float32 => Event::Number(self.ubj_reader.read_float32()?.into()),
+            UbjMarker::Float64 => Event::Number(self.ubj_reader.read_float64()?.into()),
+            UbjMarker::Char => Event::Char(self.ubj_reader.read_char()?),
+            UbjMarker::String => {
+                self.ubj_reader.read_marker(marker)?;
+                Event::String(self.read_text()?)
+            }
+            UbjMarker::HighPrecision => {
+                self.ubj_reader.read_marker(marker)?;
+                Event::HighPrecision(self.read_text()?)
+            }
+            UbjMarker::StartArray | UbjMarker::StartObject => {
+                self.ubj_reader.read_marker(marker)?;
+                let object = marker == UbjMarker::StartObject;
+                let (header, element_type) = self.read_container_header()?;
+                self.stack.push(Frame {
+                    object,
+                    element_type,
+                    remaining: header.count.map(|(count, _)| count),
+                    expecting_key: object,
+                });
+                return Ok(if object {
+                    Event::StartObject(header)
+                } else {
+                    Event::StartArray(header)
+                });
+            }
+            _ => return Err(UbjError::UnexpectedMarker(byte)),
+        };
+        self.end_value();
+        Ok(event)
+    }
+
+    fn read_container_header(&mut self) -> UbjResult<(ContainerHeader, Option<UbjMarker>)> {
+        let mut header = ContainerHeader::default();
+        let mut element_type = None;
+        if self.ubj_reader.peek_marker()? == UbjMarker::ContainerType as u8 {
+            self.ubj_reader.read_marker(UbjMarker::ContainerType)?;
+            let byte = self.ubj_reader.peek_marker()?;
+            let marker = UbjMarker::from_u8(byte).ok_or(UbjError::UnexpectedMarker(byte))?;
+            self.ubj_reader.read_marker(marker)?;
+            header.element_type = Some(byte as char);
+            element_type = Some(marker);
+
+            // The specification mandates the count to follow the type
+            let marker = self.ubj_reader.peek_marker()?;
+            if marker != UbjMarker::ContainerCount as u8 {
+                return Err(UbjError::UnexpectedMarker(marker));
+            }
+        }
+        if self.ubj_reader.peek_marker()? == UbjMarker::ContainerCount as u8 {
+            self.ubj_reader.read_marker(UbjMarker::ContainerCount)?;
+            header.count = Some(self.read_length()?);
+        }
+        Ok((header, element_type))
+    }
+
+    fn read_length(&mut self) -> UbjResult<(usize, char)> {
+        let marker = self.ubj_reader.peek_marker()?;
+        let n = self.ubj_reader.read_integer()?;
+        let len = usize::try_from(n).map_err(|_| UbjError::NegativeLength(n))?;
+        Ok((len, marker as char))
+    }
+
+    fn read_text(&mut self) -> UbjResult<Text> {
+        let (len, len_marker) = self.read_length()?;
+        let text = self.ubj_reader.read_str(len)?.into_owned();
+        Ok(Text { text, len_marker })
+    }
+}
+
+impl<'de, R> Iterator for EventReader<R>
+where
+    R: UbjRead<'de>,
+{
+    type Item = UbjResult<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.read_event().transpose();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//  W R I T E R
+// -------------------------------------------------------------------------------------------------
+
+/// A writer of events, which encodes them exactly as they tell.
+///
+/// The events of values that are elements (or values) of an optimized container of some `$` type
+/// get written without their marker, which has to match the type. Note that it is up to the
+/// caller to write as many elements (or entries) as the `#` count of a container tells.
+pub struct EventWriter<W>
+where
+    W: IoWrite,
+{
+    ubj_writer: UbjWriter<W>,
+    stack: Vec<Frame>,
+    // The bytes of the value being written, whose marker may have to be stripped
+    scratch: Vec<u8>,
+}
+
+impl<W> EventWriter<W>
+where
+    W: IoWrite,
+{
+    /// Creates a new event writer writing to the given IO writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            ubj_writer: UbjWriter::new(writer),
+            stack: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Writes the given event.
+    pub fn write(&mut self, event: &Event) -> UbjResult<()> {
+        match event {
+            Event::NoOp => self.ubj_writer.write_marker(UbjMarker::NoOp),
+            Event::Key(text) => {
+                let object = self.stack.last().is_some_and(|frame| frame.object);
+                if !object {
+                    return Err(UbjError::Unsupported("keys outside of objects"));
+                }
+                encode_text(&mut self.scratch, None, text)?;
+                self.ubj_writer.write_raw(&self.scratch)
+            }
+            Event::EndArray | Event::EndObject => {
+                let object = matches!(event, Event::EndObject);
+                let end = if object {
+                    UbjMarker::EndObject
+                } else {
+                    UbjMarker::EndArray
+                };
+                match self.stack.pop() {
+                    Some(frame) if frame.object == object => {
+                        if frame.remaining.is_none() {
+                            self.ubj_writer.write_marker(end)?;
+                        }
+                        Ok(())
+                    }
+                    _ => Err(UbjError::UnexpectedMarker(end as u8)),
+                }
+            }
+            _ => self.write_value_start(event),
+        }
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> UbjResult<()> {
+        self.ubj_writer.flush()
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.ubj_writer.into_inner()
+    }
+
+    fn write_value_start(&mut self, event: &Event) -> UbjResult<()> {
+        let scratch = &mut self.scratch;
+        scratch.clear();
+        let mut frame = None;
+        match event {
+            Event::Null => UbjWriter::new(&mut *scratch).write_null()?,
+            Event::Bool(v) => UbjWriter::new(&mut *scratch).write_bool(*v)?,
+            Event::Number(number) => to_writer(scratch, number)?,
+            Event::Char(v) => UbjWriter::new(&mut *scratch).write_char(*v)?,
+            Event::String(text) => encode_text(scratch, Some(UbjMarker::String), text)?,
+            Event::HighPrecision(text) => {
+                encode_text(scratch, Some(UbjMarker::HighPrecision), text)?
+            }
+            Event::StartArray(header) | Event::StartObject(header) => {
+                let object = matches!(event, Event::StartObject(_));
+                let marker = if object {
+                    UbjMarker::StartObject
+                } else {
+                    UbjMarker::StartArray
+                };
+                frame = Some(encode_header(scratch, marker, header)?);
+            }
+            _ => {}
+        }
+
+        // Values within optimized containers of some type do not carry their marker
+        let element_type = self.stack.last().and_then(|frame| frame.element_type);
+        let bytes = match element_type {
+            Some(marker) if self.scratch.first() == Some(&(marker as u8)) => &self.scratch[1..],
+            Some(marker) => return Err(UbjError::UnexpectedMarker(marker as u8)),
+            None => &self.scratch[..],
+        };
+        self.ubj_writer.write_raw(bytes)?;
+        self.stack.extend(frame);
+        Ok(())
+    }
+}
+
+fn encode_length(scratch: &mut Vec<u8>, len: usize, marker: char) -> UbjResult<()> {
+    let number = match marker {
+        'i' => i8::try_from(len)
+            .map(Number::from)
+            .map_err(|_| UbjError::OutOfRange("i8")),
+        'U' => u8::try_from(len)
+            .map(Number::from)
+            .map_err(|_| UbjError::OutOfRange("u8")),
+        'I' => i16::try_from(len)
+            .map(Number::from)
+            .map_err(|_| UbjError::OutOfRange("i16")),
+        'l' => i32::try_from(len)
+            .map(Number::from)
+            .map_err(|_| UbjError::OutOfRange("i32")),
+        'L' => i64::try_from(len)
+            .map(Number::from)
+            .map_err(|_| UbjError::OutOfRange("i64")),
+        _ => Err(UbjError::UnexpectedMarker(marker as u8)),
+    }?;
+    to_writer(scratch, &number)
+}
+
+fn encode_text(scratch: &mut Vec<u8>, marker: Option<UbjMarker>, text: &Text) -> UbjResult<()> {
+    scratch.clear();
+    if let Some(marker) = marker {
+        UbjWriter::new(&mut *scratch).write_marker(marker)?;
+    }
+    encode_length(scratch, text.text.len(), text.len_marker)?;
+    scratch.extend_from_slice(text.text.as_bytes());
+    Ok(())
+}
+
+fn encode_header(
+    scratch: &mut Vec<u8>,
+    marker: UbjMarker,
+    header: &ContainerHeader,
+) -> UbjResult<Frame> {
+    let mut writer = UbjWriter::new(&mut *scratch);
+    writer.write_marker(marker)?;
+    let mut element_type = None;
+    if let Some(t) = header.element_type {
+        let t = UbjMarker::from_u8(t as u8).ok_or(UbjError::UnexpectedMarker(t as u8))?;
+        writer.write_marker(UbjMarker::ContainerType)?;
+        writer.write_marker(t)?;
+        element_type = Some(t);
+    }
+    if let Some((count, len_marker)) = header.count {
+        writer.write_marker(UbjMarker::ContainerCount)?;
+        encode_length(scratch, count, len_marker)?;
+    }
+    Ok(Frame {
+        object: marker == UbjMarker::StartObject,
+        element_type,
+        remaining: header.count.map(|(count, _)| count),
+        expecting_key: false,
+    })
+}
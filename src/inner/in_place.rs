@@ -0,0 +1,108 @@
+use crate::inner::err::{UbjError, UbjResult};
+use crate::inner::reader::{IoRead, UbjReader};
+use crate::inner::ser::{UbjSerializerOptions, to_vec_with_options};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+
+/// Overwrites the value at the given JSON Pointer (such as `/a/0/b`) with the given one, right
+/// where it lies in the given file, without rewriting anything else. Returns `false` if there is
+/// no value at that pointer, leaving the file untouched.
+///
+/// The value gets looked up from the current position of the file (where it is left afterwards),
+/// skipping all values before it without decoding them, and its bytes get overwritten only if the
+/// new value encodes to the very same number of bytes. It fails with [`UbjError::LengthMismatch`]
+/// otherwise.
+///
+/// As numbers get written with the narrowest marker their value fits, use [`Number`] (which keeps
+/// the marker it has been built with) to replace numbers of a fixed width, or otherwise see
+/// [`patch_in_place_with_options`]. Elements (or values) of an optimized container of some `$`
+/// type must be replaced by values of that very type.
+///
+/// [`Number`]: crate::Number
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::{Number, ubj};
+/// use std::io::Cursor;
+///
+/// let mut file = Cursor::new(serde_ubj::to_vec(&ubj!({"name": "x", "count": 1000})).unwrap());
+/// assert!(serde_ubj::patch_in_place(&mut file, "/count", &Number::from(1001_i16)).unwrap());
+///
+/// let value: serde_ubj::Value = serde_ubj::from_buf_reader(&mut file).unwrap();
+/// assert_eq!(value, ubj!({"name": "x", "count": 1001}));
+/// ```
+pub fn patch_in_place<F, T>(file: &mut F, pointer: &str, value: &T) -> UbjResult<bool>
+where
+    F: Read + Write + Seek,
+    T: serde::Serialize,
+{
+    patch_in_place_with_options(file, pointer, value, UbjSerializerOptions::default())
+}
+
+/// Overwrites the value at the given JSON Pointer (such as `/a/0/b`) with the given one, right
+/// where it lies in the given file, as serialized with the given options (see [`patch_in_place`]).
+///
+/// Files written with fixed-width integers (and lengths) can be patched with the very same
+/// options, as all values of a given Rust type then encode to the same number of bytes.
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::UbjSerializerOptions;
+/// use std::collections::BTreeMap;
+/// use std::io::Cursor;
+///
+/// let options = UbjSerializerOptions::new().fixed_width_integers(true);
+/// let counters = BTreeMap::from([("hits", 7_i32)]);
+/// let mut file = Cursor::new(serde_ubj::to_vec_with_options(&counters, options).unwrap());
+/// assert!(serde_ubj::patch_in_place_with_options(&mut file, "/hits", &70_000_i32, options).unwrap());
+///
+/// let counters: BTreeMap<String, i32> = serde_ubj::from_buf_reader(&mut file).unwrap();
+/// assert_eq!(counters["hits"], 70_000);
+/// ```
+pub fn patch_in_place_with_options<F, T>(
+    file: &mut F,
+    pointer: &str,
+    value: &T,
+    options: UbjSerializerOptions,
+) -> UbjResult<bool>
+where
+    F: Read + Write + Seek,
+    T: serde::Serialize,
+{
+    let new = to_vec_with_options(value, options)?;
+    let start = file.stream_position().map_err(UbjError::from_io_error)?;
+    let result = patch_at(file, start, pointer, new);
+    // The reader buffers ahead, so the position gets restored whatever happened
+    file.seek(SeekFrom::Start(start))
+        .map_err(UbjError::from_io_error)?;
+    result
+}
+
+fn patch_at<F>(file: &mut F, start: u64, pointer: &str, mut new: Vec<u8>) -> UbjResult<bool>
+where
+    F: Read + Write + Seek,
+{
+    let mut reader = UbjReader::new(IoRead::new(BufReader::new(&mut *file)));
+    if !reader.find_pointer(pointer)? {
+        return Ok(false);
+    }
+    let offset = reader.bytes_consumed();
+    let old = reader.read_raw_value()?;
+    let old_len = reader.bytes_consumed() - offset;
+
+    if old.len() > old_len {
+        // The old value is within an optimized container, which implies its marker
+        if new.first() != old.first() {
+            return Err(UbjError::UnexpectedMarker(old[0]));
+        }
+        new.remove(0);
+    }
+    if new.len() != old_len {
+        return Err(UbjError::LengthMismatch(old_len, new.len()));
+    }
+
+    file.seek(SeekFrom::Start(start + offset as u64))
+        .map_err(UbjError::from_io_error)?;
+    file.write_all(&new).map_err(UbjError::from_io_error)?;
+    file.flush().map_err(UbjError::from_io_error)?;
+    Ok(true)
+}
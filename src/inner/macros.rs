@@ -0,0 +1,147 @@
+/// Builds a [`Value`](crate::Value) out of a JSON-like literal.
+///
+/// Any Rust expression can be interpolated, as long as its type implements `serde::Serialize`
+/// (in which case it becomes what [`to_value`](crate::to_value) would give, so that integers get
+/// the narrowest marker their value fits). The following annotations force the very marker a
+/// number is built with instead, such as to build non-minimal encodings on purpose:
+///
+/// | annotation               | marker |
+/// |--------------------------|--------|
+/// | `int8(v)`                | `i`    |
+/// | `uint8(v)`               | `U`    |
+/// | `int16(v)`               | `I`    |
+/// | `int32(v)`               | `l`    |
+/// | `int64(v)`               | `L`    |
+/// | `float32(v)`             | `d`    |
+/// | `float64(v)`             | `D`    |
+/// | `char(v)`                | `C`    |
+/// | `high_precision("text")` | `H`    |
+///
+/// Keys of objects are either string literals or parenthesized expressions.
+///
+/// # Panics
+/// If an interpolated expression cannot be converted to a value (such as a map whose keys are
+/// not strings).
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::{ubj, Value};
+///
+/// let value = ubj!({"name": "x", "ids": [1, 2, int16(3)], "parent": null});
+/// assert_eq!(value["ids"][2], Value::Int16(3));
+/// assert_eq!(
+///     serde_ubj::to_vec(&value).unwrap(),
+///     vec![
+///         0x7B, 0x23, 0x55, 0x03,
+///             0x55, 0x04, b'n', b'a', b'm', b'e',  0x53, 0x55, 0x01, b'x',
+///             0x55, 0x03, b'i', b'd', b's',        0x5B, 0x23, 0x55, 0x03, 0x55, 0x01, 0x55, 0x02, 0x49, 0x00, 0x03,
+///             0x55, 0x06, b'p', b'a', b'r', b'e', b'n', b't',  0x5A,
+///     ]
+/// );
+/// ```
+#[macro_export]
+macro_rules! ubj {
+    // ---------------------------------------------------------------------------------
+    //  Elements of an array, munched one at a time
+    // ---------------------------------------------------------------------------------
+
+    (@array [$($elements:expr,)*]) => {
+        $crate::__private::vec![$($elements,)*]
+    };
+    (@array [$($elements:expr,)*] null $(, $($rest:tt)*)?) => {
+        $crate::ubj!(@array [$($elements,)* $crate::Value::Null,] $($($rest)*)?)
+    };
+    (@array [$($elements:expr,)*] [$($array:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::ubj!(@array [$($elements,)* $crate::ubj!([$($array)*]),] $($($rest)*)?)
+    };
+    (@array [$($elements:expr,)*] {$($object:tt)*} $(, $($rest:tt)*)?) => {
+        $crate::ubj!(@array [$($elements,)* $crate::ubj!({$($object)*}),] $($($rest)*)?)
+    };
+    (@array [$($elements:expr,)*] $annotation:ident ($($v:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::ubj!(@array [$($elements,)* $crate::ubj!($annotation($($v)*)),] $($($rest)*)?)
+    };
+    (@array [$($elements:expr,)*] $next:expr $(, $($rest:tt)*)?) => {
+        $crate::ubj!(@array [$($elements,)* $crate::ubj!($next),] $($($rest)*)?)
+    };
+
+    // ---------------------------------------------------------------------------------
+    //  Entries of an object, munched one at a time
+    // ---------------------------------------------------------------------------------
+
+    (@object $map:ident ()) => {};
+    (@object $map:ident ($key:tt : null $(, $($rest:tt)*)?)) => {
+        $crate::ubj!(@entry $map $key ($crate::Value::Null));
+        $crate::ubj!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:tt : [$($array:tt)*] $(, $($rest:tt)*)?)) => {
+        $crate::ubj!(@entry $map $key ($crate::ubj!([$($array)*])));
+        $crate::ubj!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:tt : {$($object:tt)*} $(, $($rest:tt)*)?)) => {
+        $crate::ubj!(@entry $map $key ($crate::ubj!({$($object)*})));
+        $crate::ubj!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:tt : $annotation:ident ($($v:tt)*) $(, $($rest:tt)*)?)) => {
+        $crate::ubj!(@entry $map $key ($crate::ubj!($annotation($($v)*))));
+        $crate::ubj!(@object $map ($($($rest)*)?));
+    };
+    (@object $map:ident ($key:tt : $value:expr $(, $($rest:tt)*)?)) => {
+        $crate::ubj!(@entry $map $key ($crate::ubj!($value)));
+        $crate::ubj!(@object $map ($($($rest)*)?));
+    };
+    (@entry $map:ident $key:tt ($value:expr)) => {
+        $map.insert($crate::__private::String::from($key), $value);
+    };
+
+    // ---------------------------------------------------------------------------------
+    //  Values
+    // ---------------------------------------------------------------------------------
+
+    (null) => {
+        $crate::Value::Null
+    };
+    (int8($v:expr)) => {
+        $crate::Value::Int8($v)
+    };
+    (uint8($v:expr)) => {
+        $crate::Value::Uint8($v)
+    };
+    (int16($v:expr)) => {
+        $crate::Value::Int16($v)
+    };
+    (int32($v:expr)) => {
+        $crate::Value::Int32($v)
+    };
+    (int64($v:expr)) => {
+        $crate::Value::Int64($v)
+    };
+    (float32($v:expr)) => {
+        $crate::Value::Float32($v)
+    };
+    (float64($v:expr)) => {
+        $crate::Value::Float64($v)
+    };
+    (char($v:expr)) => {
+        $crate::Value::Char($v)
+    };
+    (high_precision($v:expr)) => {
+        $crate::Value::HighPrecision($crate::__private::String::from($v))
+    };
+    ([]) => {
+        $crate::Value::Array($crate::__private::vec![])
+    };
+    ([$($array:tt)+]) => {
+        $crate::Value::Array($crate::ubj!(@array [] $($array)+))
+    };
+    ({}) => {
+        $crate::Value::Object($crate::Map::new())
+    };
+    ({$($object:tt)+}) => {{
+        let mut map = $crate::Map::new();
+        $crate::ubj!(@object map ($($object)+));
+        $crate::Value::Object(map)
+    }};
+    ($other:expr) => {
+        $crate::to_value(&$other).unwrap()
+    };
+}
@@ -1,5 +1,6 @@
 
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum UbjMarker {
     Null         = 0x5A,  // Z
     True         = 0x54,  // T
@@ -13,10 +14,87 @@ pub enum UbjMarker {
     Float64      = 0x44,  // D
     Char         = 0x43,  // C
     String       = 0x53,  // S
+    HighPrecision = 0x48,  // H  (decimal text of arbitrary precision)
+    NoOp         = 0x4E,  // N  (no value at all, just padding)
 
     StartArray   = 0x5B,  // [
     EndArray     = 0x5D,  // ]
 
     StartObject  = 0x7B,  // {
     EndObject    = 0x7D,  // }
+
+    ContainerType  = 0x24,  // $
+    ContainerCount = 0x23,  // #
+}
+
+impl UbjMarker {
+    /// Returns the marker corresponding to the given byte, if any.
+    pub fn from_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0x5A => Some(UbjMarker::Null),
+            0x54 => Some(UbjMarker::True),
+            0x46 => Some(UbjMarker::False),
+            0x69 => Some(UbjMarker::Int8),
+            0x55 => Some(UbjMarker::Uint8),
+            0x49 => Some(UbjMarker::Int16),
+            0x6C => Some(UbjMarker::Int32),
+            0x4C => Some(UbjMarker::Int64),
+            0x64 => Some(UbjMarker::Float32),
+            0x44 => Some(UbjMarker::Float64),
+            0x43 => Some(UbjMarker::Char),
+            0x53 => Some(UbjMarker::String),
+            0x48 => Some(UbjMarker::HighPrecision),
+            0x4E => Some(UbjMarker::NoOp),
+            0x5B => Some(UbjMarker::StartArray),
+            0x5D => Some(UbjMarker::EndArray),
+            0x7B => Some(UbjMarker::StartObject),
+            0x7D => Some(UbjMarker::EndObject),
+            0x24 => Some(UbjMarker::ContainerType),
+            0x23 => Some(UbjMarker::ContainerCount),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of payload bytes following an integer marker, if this is one.
+    pub fn integer_width(&self) -> Option<usize> {
+        match self {
+            UbjMarker::Int8 | UbjMarker::Uint8 => Some(1),
+            UbjMarker::Int16 => Some(2),
+            UbjMarker::Int32 => Some(4),
+            UbjMarker::Int64 => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of payload bytes following the marker of a fixed-size value, if this
+    /// is one.
+    pub fn fixed_payload_width(&self) -> Option<usize> {
+        match self {
+            UbjMarker::Null | UbjMarker::True | UbjMarker::False => Some(0),
+            UbjMarker::Char => Some(1),
+            UbjMarker::Float32 => Some(4),
+            UbjMarker::Float64 => Some(8),
+            _ => self.integer_width(),
+        }
+    }
+
+    /// Tells whether values of this type can be the declared `$` type of an optimized container
+    /// written by this crate.
+    //
+    // The specification would also allow no-payload types (such as null and booleans) and even
+    // nested containers, but quite a few decoders out there do not cope with them.
+    pub fn is_container_type(&self) -> bool {
+        matches!(
+            self,
+            UbjMarker::Int8
+                | UbjMarker::Uint8
+                | UbjMarker::Int16
+                | UbjMarker::Int32
+                | UbjMarker::Int64
+                | UbjMarker::Float32
+                | UbjMarker::Float64
+                | UbjMarker::Char
+                | UbjMarker::String
+        )
+    }
 }
\ No newline at end of file
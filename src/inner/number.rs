@@ -0,0 +1,286 @@
+use crate::inner::value::{
+    HIGH_PRECISION_TOKEN, INT16_TOKEN, INT32_TOKEN, INT64_TOKEN, VALUE_TOKEN, Value, ValueVisitor,
+};
+use alloc::string::String;
+use core::fmt;
+use serde::de::{EnumAccess, Error, Visitor};
+
+/// A number, which keeps the very marker it has been read (or built) with, and gets written back
+/// with that marker.
+///
+/// Deserializing a number accepts any of the `i`, `U`, `I`, `l`, `L`, `d`, `D` and `H` markers,
+/// whereas serializing it writes the same marker (rather than the narrowest one its value fits),
+/// so that relayed numbers come out exactly as they came in.
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::Number;
+///
+/// //           [l]   [7]
+/// let bytes = [0x6C, 0x00, 0x00, 0x00, 0x07];
+/// let number: Number = serde_ubj::from_slice(&bytes).unwrap();
+/// assert_eq!(number.marker(), 'l');
+/// assert_eq!(number.as_i64(), Some(7));
+/// assert_eq!(serde_ubj::to_vec(&number).unwrap(), bytes);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Number {
+    n: N,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum N {
+    Int8(i8),
+    Uint8(u8),
+    Int16(i16),
+    Int32(i32),
+    Int64(i64),
+    Float32(f32),
+    Float64(f64),
+    HighPrecision(String),
+}
+
+impl Number {
+    /// Creates a high-precision number out of its decimal text, which is not validated.
+    pub fn high_precision<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            n: N::HighPrecision(text.into()),
+        }
+    }
+
+    /// Returns the marker this number gets written with.
+    pub fn marker(&self) -> char {
+        match self.n {
+            N::Int8(_) => 'i',
+            N::Uint8(_) => 'U',
+            N::Int16(_) => 'I',
+            N::Int32(_) => 'l',
+            N::Int64(_) => 'L',
+            N::Float32(_) => 'd',
+            N::Float64(_) => 'D',
+            N::HighPrecision(_) => 'H',
+        }
+    }
+
+    /// Tells whether this is an integer (of any width), rather than a floating point or a
+    /// high-precision number.
+    pub fn is_integer(&self) -> bool {
+        !matches!(self.n, N::Float32(_) | N::Float64(_) | N::HighPrecision(_))
+    }
+
+    /// Returns the number as `i64`, if this is an integer (of any width) that fits it.
+    ///
+    /// High-precision numbers are parsed, as long as they are integers that fit.
+    pub fn as_i64(&self) -> Option<i64> {
+        match &self.n {
+            N::Int8(v) => Some(*v as i64),
+            N::Uint8(v) => Some(*v as i64),
+            N::Int16(v) => Some(*v as i64),
+            N::Int32(v) => Some(*v as i64),
+            N::Int64(v) => Some(*v),
+            N::HighPrecision(text) => text.parse().ok(),
+            N::Float32(_) | N::Float64(_) => None,
+        }
+    }
+
+    /// Returns the number as `u64`, if this is an integer (of any width) that fits it.
+    ///
+    /// High-precision numbers are parsed, as long as they are integers that fit.
+    pub fn as_u64(&self) -> Option<u64> {
+        match &self.n {
+            N::HighPrecision(text) => text.parse().ok(),
+            _ => self.as_i64().and_then(|v| u64::try_from(v).ok()),
+        }
+    }
+
+    /// Returns the number as `f64` (possibly losing precision), unless this is a high-precision
+    /// number whose text cannot be parsed.
+    pub fn as_f64(&self) -> Option<f64> {
+        match &self.n {
+            N::Float32(v) => Some(*v as f64),
+            N::Float64(v) => Some(*v),
+            N::HighPrecision(text) => text.parse().ok(),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+
+    /// Returns the decimal text, if this is a high-precision number.
+    pub fn as_high_precision(&self) -> Option<&str> {
+        match &self.n {
+            N::HighPrecision(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            /// Keeps the marker of the Rust type, rather than the narrowest one the value fits.
+            impl From<$t> for Number {
+                fn from(v: $t) -> Self {
+                    Self { n: N::$variant(v) }
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    i8 => Int8,
+    u8 => Uint8,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    f32 => Float32,
+    f64 => Float64,
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        match number.n {
+            N::Int8(v) => Value::Int8(v),
+            N::Uint8(v) => Value::Uint8(v),
+            N::Int16(v) => Value::Int16(v),
+            N::Int32(v) => Value::Int32(v),
+            N::Int64(v) => Value::Int64(v),
+            N::Float32(v) => Value::Float32(v),
+            N::Float64(v) => Value::Float64(v),
+            N::HighPrecision(text) => Value::HighPrecision(text),
+        }
+    }
+}
+
+impl Value {
+    /// Returns the number, if this is a number of any kind.
+    pub fn as_number(&self) -> Option<Number> {
+        let n = match self {
+            Value::Int8(v) => N::Int8(*v),
+            Value::Uint8(v) => N::Uint8(*v),
+            Value::Int16(v) => N::Int16(*v),
+            Value::Int32(v) => N::Int32(*v),
+            Value::Int64(v) => N::Int64(*v),
+            Value::Float32(v) => N::Float32(*v),
+            Value::Float64(v) => N::Float64(*v),
+            Value::HighPrecision(text) => N::HighPrecision(text.clone()),
+            _ => return None,
+        };
+        Some(Number { n })
+    }
+}
+
+/// Renders the number as JSON text, just like [`Value`] does.
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.n {
+            N::Int8(v) => write!(f, "{v}"),
+            N::Uint8(v) => write!(f, "{v}"),
+            N::Int16(v) => write!(f, "{v}"),
+            N::Int32(v) => write!(f, "{v}"),
+            N::Int64(v) => write!(f, "{v}"),
+            N::Float32(v) if v.is_finite() => write!(f, "{v}"),
+            N::Float64(v) if v.is_finite() => write!(f, "{v}"),
+            N::Float32(_) | N::Float64(_) => f.write_str("null"),
+            N::HighPrecision(v) => f.write_str(v),
+        }
+    }
+}
+
+impl serde::Serialize for Number {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match &self.n {
+            N::Int8(v) => serializer.serialize_i8(*v),
+            N::Uint8(v) => serializer.serialize_u8(*v),
+            N::Int16(v) => serializer.serialize_newtype_struct(INT16_TOKEN, v),
+            N::Int32(v) => serializer.serialize_newtype_struct(INT32_TOKEN, v),
+            N::Int64(v) => serializer.serialize_newtype_struct(INT64_TOKEN, v),
+            N::Float32(v) => serializer.serialize_f32(*v),
+            N::Float64(v) => serializer.serialize_f64(*v),
+            N::HighPrecision(v) => {
+                serializer.serialize_newtype_struct(HIGH_PRECISION_TOKEN, v.as_str())
+            }
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Number {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // Just like values, so that high-precision numbers get told apart from text
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, NumberVisitor)
+    }
+}
+
+/// Accepts numbers only, failing right away on anything else (without reading it any further).
+struct NumberVisitor;
+
+/// Turns the value the Value visitor gives for a number into that number.
+fn number<E>(value: Result<Value, E>) -> Result<Number, E>
+where
+    E: Error,
+{
+    let value = value?;
+    value
+        .as_number()
+        .ok_or_else(|| E::invalid_type(value.unexpected(), &"a number"))
+}
+
+macro_rules! visit_numbers {
+    ($($visit:ident($t:ty)),* $(,)?) => {
+        $(
+            fn $visit<E>(self, v: $t) -> Result<Number, E>
+            where
+                E: Error,
+            {
+                // Values keep the marker of numbers already
+                number(ValueVisitor.$visit(v))
+            }
+        )*
+    };
+}
+
+impl<'de> Visitor<'de> for NumberVisitor {
+    type Value = Number;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Number, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    visit_numbers! {
+        visit_i8(i8),
+        visit_u8(u8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_i128(i128),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Number, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        // Our deserializers tell about a high-precision number this way
+        number(ValueVisitor.visit_enum(data))
+    }
+}
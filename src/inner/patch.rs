@@ -0,0 +1,279 @@
+use crate::inner::IoWrite;
+use crate::inner::de::from_slice;
+use crate::inner::err::UbjResult;
+use crate::inner::markers::UbjMarker;
+use crate::inner::reader::{UbjContainerHeader, UbjRead, UbjReader};
+use crate::inner::ser::to_vec;
+use crate::inner::value::{Map, Value};
+use crate::inner::writer::UbjWriter;
+use alloc::borrow::Cow;
+use alloc::vec;
+
+impl Value {
+    /// Applies the given JSON Merge Patch (as defined by RFC 7396) to this value.
+    ///
+    /// Objects of the patch get merged recursively, where `null` values remove the entries they
+    /// are keyed by, whereas anything else (arrays included) replaces the value as a whole.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use serde_ubj::ubj;
+    ///
+    /// let mut value = ubj!({"a": 1, "b": {"c": 2, "d": 3}});
+    /// value.merge_patch(&ubj!({"a": null, "b": {"c": [4]}}));
+    /// assert_eq!(value, ubj!({"b": {"c": [4], "d": 3}}));
+    /// ```
+    pub fn merge_patch(&mut self, patch: &Value) {
+        let Value::Object(patch_entries) = patch else {
+            *self = patch.clone();
+            return;
+        };
+        if !matches!(self, Value::Object(_)) {
+            *self = Value::Object(Map::new());
+        }
+        if let Value::Object(entries) = self {
+            for (key, value) in patch_entries {
+                if value.is_null() {
+                    entries.remove(key);
+                } else {
+                    entries.entry(key).merge_patch(value);
+                }
+            }
+        }
+    }
+
+    /// Gives the JSON Merge Patch (as defined by RFC 7396) which turns this value into the given
+    /// target value, when applied by means of [`merge_patch`](Value::merge_patch).
+    ///
+    /// Note that merge patches can neither set `null` values within objects (as these remove
+    /// entries instead) nor change single elements of arrays (which get replaced as a whole).
+    /// Also, values are told apart by their markers too, so that `Uint8(1)` differs from `Int8(1)`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use serde_ubj::ubj;
+    ///
+    /// let source = ubj!({"a": 1, "b": {"c": 2, "d": 3}});
+    /// let target = ubj!({"b": {"c": [4], "d": 3}});
+    /// assert_eq!(source.merge_diff(&target), ubj!({"a": null, "b": {"c": [4]}}));
+    /// ```
+    pub fn merge_diff(&self, target: &Value) -> Value {
+        match (self, target) {
+            (Value::Object(source), Value::Object(target)) => {
+                Value::Object(diff_entries(source, target))
+            }
+            _ => target.clone(),
+        }
+    }
+}
+
+fn diff_entries(source: &Map, target: &Map) -> Map {
+    let mut patch: Map = source
+        .keys()
+        .filter(|key| !target.contains_key(key))
+        .map(|key| (key.as_str(), Value::Null))
+        .collect();
+    for (key, value) in target {
+        match (source.get(key), value) {
+            (Some(previous), _) if previous == value => {}
+            (Some(Value::Object(previous)), Value::Object(value)) => {
+                let nested = diff_entries(previous, value);
+                if !nested.is_empty() {
+                    patch.insert(key.clone(), Value::Object(nested));
+                }
+            }
+            _ => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    patch
+}
+
+/// The entries of an object being read, one at a time.
+struct Entries {
+    header: UbjContainerHeader,
+    remaining: Option<usize>,
+}
+
+impl Entries {
+    fn start<'de, R>(reader: &mut UbjReader<R>) -> UbjResult<Self>
+    where
+        R: UbjRead<'de>,
+    {
+        let header = reader.read_start_object()?;
+        Ok(Self {
+            header,
+            remaining: header.count,
+        })
+    }
+
+    /// Reads the key of the next entry (whose value is then the next one to be read), or the
+    /// end of the object.
+    fn next_key<'de, R>(&mut self, reader: &mut UbjReader<R>) -> UbjResult<Option<Cow<'de, str>>>
+    where
+        R: UbjRead<'de>,
+    {
+        match &mut self.remaining {
+            Some(0) => return Ok(None),
+            Some(n) => *n -= 1,
+            None if reader.peek_marker()? == UbjMarker::EndObject as u8 => {
+                reader.read_end_object()?;
+                return Ok(None);
+            }
+            None => {}
+        }
+        let key = reader.read_unmarked_str()?;
+        reader.imply_marker(self.header.element_type);
+        Ok(Some(key))
+    }
+
+    /// Reads all the entries left.
+    fn read_rest<'de, R>(&mut self, reader: &mut UbjReader<R>) -> UbjResult<Map>
+    where
+        R: UbjRead<'de>,
+    {
+        let mut rest = Map::new();
+        while let Some(key) = self.next_key(reader)? {
+            let value = reader.read_value()?;
+            rest.insert(key.into_owned(), value);
+        }
+        Ok(rest)
+    }
+}
+
+impl<'de, R> UbjReader<R>
+where
+    R: UbjRead<'de>,
+{
+    /// Reads the next value, by decoding its raw bytes.
+    fn read_value(&mut self) -> UbjResult<Value> {
+        from_slice(&self.read_raw_value()?)
+    }
+
+    fn is_object_next(&mut self) -> UbjResult<bool> {
+        Ok(self.peek_marker()? == UbjMarker::StartObject as u8)
+    }
+
+    /// Reads the next value while applying the given JSON Merge Patch to it, and writes the
+    /// result. Values not touched by the patch get copied as they are, without being decoded,
+    /// whereas the objects the patch gets merged into are written in the plain format.
+    pub fn merge_patch_to<W>(&mut self, patch: &Value, writer: &mut UbjWriter<W>) -> UbjResult<()>
+    where
+        W: IoWrite,
+    {
+        let Value::Object(patch_entries) = patch else {
+            self.skip_value()?;
+            return writer.write_raw(&to_vec(patch)?);
+        };
+        if !self.is_object_next()? {
+            self.skip_value()?;
+            let mut merged = Value::Null;
+            merged.merge_patch(patch);
+            return writer.write_raw(&to_vec(&merged)?);
+        }
+
+        let mut entries = Entries::start(self)?;
+        let mut patched = vec![false; patch_entries.len()];
+        writer.write_start_object()?;
+        while let Some(key) = entries.next_key(self)? {
+            match patch_entries.get_full(&key) {
+                None => {
+                    writer.write_unmarked_string(&key)?;
+                    writer.write_raw(&self.read_raw_value()?)?;
+                }
+                Some((index, value)) => {
+                    patched[index] = true;
+                    if value.is_null() {
+                        self.skip_value()?;
+                    } else {
+                        writer.write_unmarked_string(&key)?;
+                        self.merge_patch_to(value, writer)?;
+                    }
+                }
+            }
+        }
+        // Then, the entries the patch adds
+        for ((key, value), _) in patch_entries.iter().zip(patched).filter(|(_, p)| !p) {
+            if !value.is_null() {
+                let mut merged = Value::Null;
+                merged.merge_patch(value);
+                writer.write_unmarked_string(key)?;
+                writer.write_raw(&to_vec(&merged)?)?;
+            }
+        }
+        writer.write_end_object()
+    }
+
+    /// Reads the next value of both this and the target reader, and gives the JSON Merge Patch
+    /// which turns the former into the latter.
+    ///
+    /// As long as two objects list the same keys in the same order, their values get compared
+    /// one entry at a time (by their raw bytes first) so that only the entries which differ get
+    /// decoded. The entries after the first mismatching key get all decoded instead.
+    pub fn merge_diff<'t, T>(&mut self, target: &mut UbjReader<T>) -> UbjResult<Value>
+    where
+        T: UbjRead<'t>,
+    {
+        if self.is_object_next()? && target.is_object_next()? {
+            Ok(Value::Object(self.merge_diff_entries(target)?))
+        } else {
+            self.skip_value()?;
+            target.read_value()
+        }
+    }
+
+    fn merge_diff_entries<'t, T>(&mut self, target: &mut UbjReader<T>) -> UbjResult<Map>
+    where
+        T: UbjRead<'t>,
+    {
+        let mut source_entries = Entries::start(self)?;
+        let mut target_entries = Entries::start(target)?;
+        let mut patch = Map::new();
+        loop {
+            let source_key = source_entries.next_key(self)?;
+            let target_key = target_entries.next_key(target)?;
+            match (source_key, target_key) {
+                (None, None) => return Ok(patch),
+                (Some(source_key), Some(target_key)) if source_key == target_key => {
+                    if let Some(value) = self.diff_entry(target)? {
+                        patch.insert(target_key.into_owned(), value);
+                    }
+                }
+                (source_key, target_key) => {
+                    let mut source_rest = Map::new();
+                    if let Some(key) = source_key {
+                        source_rest.insert(key.into_owned(), self.read_value()?);
+                        source_rest.extend(source_entries.read_rest(self)?);
+                    }
+                    let mut target_rest = Map::new();
+                    if let Some(key) = target_key {
+                        target_rest.insert(key.into_owned(), target.read_value()?);
+                        target_rest.extend(target_entries.read_rest(target)?);
+                    }
+                    patch.extend(diff_entries(&source_rest, &target_rest));
+                    return Ok(patch);
+                }
+            }
+        }
+    }
+
+    fn diff_entry<'t, T>(&mut self, target: &mut UbjReader<T>) -> UbjResult<Option<Value>>
+    where
+        T: UbjRead<'t>,
+    {
+        if self.is_object_next()? && target.is_object_next()? {
+            let nested = self.merge_diff_entries(target)?;
+            return Ok((!nested.is_empty()).then_some(Value::Object(nested)));
+        }
+        let source_bytes = self.read_raw_value()?;
+        let target_bytes = target.read_raw_value()?;
+        if source_bytes == target_bytes {
+            return Ok(None);
+        }
+        // The same value could be encoded differently (such as in the optimized format)
+        let source: Value = from_slice(&source_bytes)?;
+        let target: Value = from_slice(&target_bytes)?;
+        Ok((source != target).then_some(target))
+    }
+}
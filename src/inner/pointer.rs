@@ -0,0 +1,143 @@
+use crate::inner::err::UbjResult;
+use crate::inner::markers::UbjMarker;
+use crate::inner::reader::{UbjRead, UbjReader};
+use crate::inner::value::Value;
+use alloc::borrow::Cow;
+
+/// Splits a JSON Pointer (as defined by RFC 6901) into its reference tokens, unescaping `~1` into
+/// `/` and `~0` into `~`. Returns `None` if the pointer is neither empty nor starting with `/`.
+fn reference_tokens(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return None;
+    }
+    let tokens = pointer.split('/').skip(1).map(|token| {
+        if token.contains('~') {
+            Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+        } else {
+            Cow::Borrowed(token)
+        }
+    });
+    Some(tokens)
+}
+
+/// Parses a reference token as the index of an array element, which has no leading zeros.
+fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    if !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl Value {
+    /// Looks up the value at the given JSON Pointer (such as `/a/0/b`), if any.
+    ///
+    /// The empty pointer refers to this very value, whereas pointers not starting with `/` refer
+    /// to nothing at all.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use serde_ubj::{ubj, Value};
+    ///
+    /// let value = ubj!({"a": [{"b": 7}], "c/d": true});
+    /// assert_eq!(value.pointer("/a/0/b"), Some(&Value::Uint8(7)));
+    /// assert_eq!(value.pointer("/c~1d"), Some(&Value::Bool(true)));
+    /// assert_eq!(value.pointer("/a/1"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        reference_tokens(pointer)?.try_fold(self, |target, token| match target {
+            Value::Object(entries) => entries.get(&token),
+            Value::Array(elements) => array_index(&token).and_then(|i| elements.get(i)),
+            _ => None,
+        })
+    }
+
+    /// Looks up the mutable value at the given JSON Pointer (such as `/a/0/b`), if any.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        reference_tokens(pointer)?.try_fold(self, |target, token| match target {
+            Value::Object(entries) => entries.get_mut(&token),
+            Value::Array(elements) => array_index(&token).and_then(|i| elements.get_mut(i)),
+            _ => None,
+        })
+    }
+}
+
+impl<'de, R> UbjReader<R>
+where
+    R: UbjRead<'de>,
+{
+    /// Moves forward to the value at the given JSON Pointer (such as `/a/0/b`), telling whether
+    /// it has been found. All values before it get skipped without being decoded.
+    ///
+    /// When found, the value is the next one to be read. Otherwise, the reader is left somewhere
+    /// within the container where the lookup failed.
+    pub fn find_pointer(&mut self, pointer: &str) -> UbjResult<bool> {
+        let Some(tokens) = reference_tokens(pointer) else {
+            return Ok(false);
+        };
+        for token in tokens {
+            let marker = self.peek_marker()?;
+            let found = if marker == UbjMarker::StartObject as u8 {
+                self.find_entry(&token)?
+            } else if marker == UbjMarker::StartArray as u8 {
+                match array_index(&token) {
+                    Some(index) => self.find_element(index)?,
+                    None => false,
+                }
+            } else {
+                false
+            };
+            if !found {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn find_entry(&mut self, key: &str) -> UbjResult<bool> {
+        let header = self.read_start_object()?;
+        let mut remaining = header.count;
+        loop {
+            if remaining != Some(0) {
+                self.skip_no_ops()?;
+            }
+            match remaining {
+                Some(0) => return Ok(false),
+                Some(n) => remaining = Some(n - 1),
+                None if self.peek_marker()? == UbjMarker::EndObject as u8 => return Ok(false),
+                None => {}
+            }
+            let found = self.read_unmarked_str()? == key;
+            self.imply_marker(header.element_type);
+            self.skip_no_ops()?;
+            if found {
+                return Ok(true);
+            }
+            self.skip_value()?;
+        }
+    }
+
+    fn find_element(&mut self, index: usize) -> UbjResult<bool> {
+        let header = self.read_start_array()?;
+        for i in 0..=index {
+            match header.count {
+                Some(count) if i >= count => return Ok(false),
+                Some(_) => {}
+                None => {
+                    self.skip_no_ops()?;
+                    if self.peek_marker()? == UbjMarker::EndArray as u8 {
+                        return Ok(false);
+                    }
+                }
+            }
+            self.imply_marker(header.element_type);
+            self.skip_no_ops()?;
+            if i < index {
+                self.skip_value()?;
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,223 @@
+use crate::inner::de::from_slice;
+use crate::inner::err::UbjResult;
+use crate::inner::reader::{SliceRead, UbjReader};
+use crate::inner::ser::to_vec;
+use crate::inner::value::RAW_VALUE_TOKEN;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::Visitor;
+
+/// The exact bytes of one Universal Binary JSON value (including all values nested within it),
+/// left undecoded.
+///
+/// Deserializing a raw value captures the bytes of the next value without decoding them, whereas
+/// serializing it copies them verbatim. It comes in handy to pass payloads through untouched, when
+/// only the envelope around them is typed. Values that are elements (or values) of an optimized
+/// container of some `$` type get the marker of that type prepended, so that the bytes always make
+/// a value on their own.
+///
+/// See [`BorrowedRawValue`] to borrow the bytes from the input, rather than copying them.
+///
+/// # Examples
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use serde_ubj::RawValue;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Envelope {
+///     id: u8,
+///     payload: RawValue,
+/// }
+///
+/// let bytes = serde_ubj::to_vec(&serde_ubj::ubj!({"id": 1, "payload": [1, "two", 3.0]})).unwrap();
+/// let envelope: Envelope = serde_ubj::from_slice(&bytes).unwrap();
+/// assert_eq!(envelope.payload.as_bytes()[0], b'[');
+/// assert_eq!(serde_ubj::to_vec(&envelope).unwrap(), bytes);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawValue {
+    bytes: Vec<u8>,
+}
+
+/// The exact bytes of one Universal Binary JSON value, just like [`RawValue`], but borrowed from
+/// the slice being deserialized.
+///
+/// Deserializing it works only via [`from_slice`](crate::from_slice) (or a deserializer reading
+/// from a slice), and only for values that carry their own marker, that is which are not elements
+/// (or values) of an optimized container of some `$` type. Fields of this type need the
+/// `#[serde(borrow)]` attribute when deserialization is derived.
+///
+/// # Examples
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_ubj::BorrowedRawValue;
+///
+/// #[derive(Deserialize)]
+/// struct Envelope<'a> {
+///     id: u8,
+///     #[serde(borrow)]
+///     payload: BorrowedRawValue<'a>,
+/// }
+///
+/// let bytes = serde_ubj::to_vec(&serde_ubj::ubj!({"id": 1, "payload": "text"})).unwrap();
+/// let envelope: Envelope = serde_ubj::from_slice(&bytes).unwrap();
+/// assert_eq!(envelope.payload.as_bytes(), &bytes[19..26]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BorrowedRawValue<'a> {
+    bytes: &'a [u8],
+}
+
+/// Makes sure the given bytes make exactly one value.
+fn validate(bytes: &[u8]) -> UbjResult<()> {
+    let mut ubj_reader = UbjReader::new(SliceRead::new(bytes));
+    ubj_reader.skip_value()?;
+    ubj_reader.read_end()
+}
+
+impl RawValue {
+    /// Creates a raw value out of the given bytes, failing unless they make exactly one value.
+    pub fn new(bytes: Vec<u8>) -> UbjResult<Self> {
+        validate(&bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Creates a raw value out of the bytes the given Rust value serializes to.
+    pub fn encode<T>(value: &T) -> UbjResult<Self>
+    where
+        T: serde::Serialize,
+    {
+        to_vec(value).map(|bytes| Self { bytes })
+    }
+
+    /// Deserializes the bytes into a Rust value of type `T`.
+    pub fn decode<'a, T>(&'a self) -> UbjResult<T>
+    where
+        T: serde::Deserialize<'a>,
+    {
+        from_slice(&self.bytes)
+    }
+
+    /// Returns the bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Unwraps the bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl<'a> BorrowedRawValue<'a> {
+    /// Creates a raw value out of the given bytes, failing unless they make exactly one value.
+    pub fn new(bytes: &'a [u8]) -> UbjResult<Self> {
+        validate(bytes)?;
+        Ok(Self { bytes })
+    }
+
+    /// Deserializes the bytes into a Rust value of type `T`, which may borrow from them.
+    pub fn decode<T>(&self) -> UbjResult<T>
+    where
+        T: serde::Deserialize<'a>,
+    {
+        from_slice(self.bytes)
+    }
+
+    /// Returns the bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl From<BorrowedRawValue<'_>> for RawValue {
+    fn from(raw: BorrowedRawValue<'_>) -> Self {
+        Self {
+            bytes: raw.bytes.to_vec(),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//  S E R D E
+// -------------------------------------------------------------------------------------------------
+
+// The bytes of a raw value, as the token newtype struct wraps them
+struct RawBytes<'a>(&'a [u8]);
+
+impl serde::Serialize for RawBytes<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl serde::Serialize for RawValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct(RAW_VALUE_TOKEN, &RawBytes(&self.bytes))
+    }
+}
+
+impl serde::Serialize for BorrowedRawValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct(RAW_VALUE_TOKEN, &RawBytes(self.bytes))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for RawValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(RAW_VALUE_TOKEN, RawValueVisitor)
+    }
+}
+
+impl<'de: 'a, 'a> serde::Deserialize<'de> for BorrowedRawValue<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(RAW_VALUE_TOKEN, BorrowedRawValueVisitor)
+    }
+}
+
+struct RawValueVisitor;
+
+impl<'de> Visitor<'de> for RawValueVisitor {
+    type Value = RawValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the bytes of a Universal Binary JSON value")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<RawValue, E> {
+        Ok(RawValue { bytes: v.to_vec() })
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<RawValue, E> {
+        Ok(RawValue { bytes: v })
+    }
+}
+
+struct BorrowedRawValueVisitor;
+
+impl<'de> Visitor<'de> for BorrowedRawValueVisitor {
+    type Value = BorrowedRawValue<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the borrowed bytes of a Universal Binary JSON value")
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<BorrowedRawValue<'de>, E> {
+        Ok(BorrowedRawValue { bytes: v })
+    }
+}
@@ -1,35 +1,162 @@
 use crate::inner::IoBufRead;
 use crate::inner::err::{UbjError, UbjResult};
 use crate::inner::markers::UbjMarker;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The input a UBJ reader reads from, which may (or may not) lend bytes living as long as `'de`.
+pub trait UbjRead<'de> {
+    /// Returns the buffered bytes, refilling the buffer when empty (an empty buffer means EOF).
+    fn fill_buf(&mut self) -> UbjResult<&[u8]>;
+
+    /// Marks the given number of bytes as consumed, so that they are not returned anymore.
+    fn consume(&mut self, n: usize);
+
+    /// Consumes the next `len` bytes and lends them, if this input lives as long as `'de`.
+    ///
+    /// Inputs not living that long return `None` without consuming anything, and the caller has
+    /// to copy the bytes out of the buffer instead.
+    fn borrow_bytes(&mut self, len: usize) -> UbjResult<Option<&'de [u8]>>;
+
+    /// Lends all the bytes not consumed yet (without consuming them), if this input lives as
+    /// long as `'de`. Inputs not living that long return `None`, which is the default.
+    fn remaining(&self) -> Option<&'de [u8]> {
+        None
+    }
+}
+
+/// An input backed by an IO buffering reader, which never lends any byte.
+pub struct IoRead<R> {
+    underlying: R,
+}
 
-pub struct UbjReader<R>
+impl<R> IoRead<R>
 where
     R: IoBufRead,
 {
+    /// Creates a new input reading from the given buffering reader.
+    pub fn new(buf_read: R) -> Self {
+        Self {
+            underlying: buf_read,
+        }
+    }
+
+    /// Unwraps the underlying reader, whose buffer still holds the bytes not consumed yet.
+    pub fn into_inner(self) -> R {
+        self.underlying
+    }
+}
+
+impl<'de, R> UbjRead<'de> for IoRead<R>
+where
+    R: IoBufRead,
+{
+    fn fill_buf(&mut self) -> UbjResult<&[u8]> {
+        // TODO Shouldn't we handle the std:io::Error::WouldBlock case?
+        self.underlying.fill_buf().map_err(UbjError::from_io_error)
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.underlying.consume(n);
+    }
+
+    fn borrow_bytes(&mut self, _len: usize) -> UbjResult<Option<&'de [u8]>> {
+        Ok(None)
+    }
+}
+
+/// An input backed by an in-memory slice of bytes, which lends all the bytes it is asked for.
+pub struct SliceRead<'de> {
+    slice: &'de [u8],
+}
+
+impl<'de> SliceRead<'de> {
+    /// Creates a new input reading from the given slice.
+    pub fn new(slice: &'de [u8]) -> Self {
+        Self { slice }
+    }
+
+    /// Returns the bytes not consumed yet.
+    pub fn into_inner(self) -> &'de [u8] {
+        self.slice
+    }
+}
+
+impl<'de> UbjRead<'de> for SliceRead<'de> {
+    fn fill_buf(&mut self) -> UbjResult<&[u8]> {
+        Ok(self.slice)
+    }
+
+    fn consume(&mut self, n: usize) {
+        self.slice = &self.slice[n..];
+    }
+
+    fn borrow_bytes(&mut self, len: usize) -> UbjResult<Option<&'de [u8]>> {
+        if len > self.slice.len() {
+            return Err(UbjError::UnexpectedEof);
+        }
+        let (borrowed, rest) = self.slice.split_at(len);
+        self.slice = rest;
+        Ok(Some(borrowed))
+    }
+
+    fn remaining(&self) -> Option<&'de [u8]> {
+        Some(self.slice)
+    }
+}
+
+pub struct UbjReader<R> {
     underlying: R,
     bytes_consumed: usize,
+    // The marker implied by the `$` type of the enclosing optimized container (if any)
+    // which the next value to be read does not carry
+    implied_marker: Option<UbjMarker>,
+    // The bytes consumed since a raw value started being read (if any)
+    raw_capture: Option<Vec<u8>>,
+}
+
+/// The header of a container, as optionally optimized with its `$` type and its `#` count.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UbjContainerHeader {
+    /// The type of all elements (or values) which, then, carry no marker.
+    pub element_type: Option<UbjMarker>,
+    /// The count of elements (or entries), in which case the container has no closing marker.
+    pub count: Option<usize>,
 }
 
-impl<R> UbjReader<R>
+impl<'de, R> UbjReader<R>
 where
-    R: IoBufRead,
+    R: UbjRead<'de>,
 {
-    /// Creates a new UBJ reader instance that delegates all operations to the provided reader.
-    pub fn new(buf_read: R) -> Self {
+    /// Creates a new UBJ reader instance that delegates all operations to the provided input.
+    pub fn new(input: R) -> Self {
         Self {
-            underlying: buf_read,
+            underlying: input,
             bytes_consumed: 0,
+            implied_marker: None,
+            raw_capture: None,
         }
     }
 
+    /// Unwraps the underlying input.
+    pub fn into_inner(self) -> R {
+        self.underlying
+    }
+
     fn buf_consume(&mut self, n: usize) {
+        if let Some(raw_capture) = &mut self.raw_capture {
+            // The bytes about to be consumed are still at the front of the buffer
+            if let Ok(buf) = self.underlying.fill_buf() {
+                raw_capture.extend_from_slice(&buf[..n]);
+            }
+        }
         self.underlying.consume(n);
         self.bytes_consumed += n;
     }
 
     fn buf_refill(&mut self) -> UbjResult<&[u8]> {
-        // TODO Shouldn't we handle the std:io::Error::WouldBlock case?
-        let buf = self.underlying.fill_buf().map_err(UbjError::from_io_error)?;
+        let buf = self.underlying.fill_buf()?;
         if buf.is_empty() {
             Err(UbjError::UnexpectedEof)
         } else {
@@ -37,7 +164,25 @@ where
         }
     }
 
+    fn buf_peek_marker(&mut self) -> UbjResult<u8> {
+        match self.implied_marker {
+            Some(implied) => Ok(implied as u8),
+            None => self.buf_refill().map(|buf| buf[0]),
+        }
+    }
+
     fn buf_consume_marker(&mut self, marker: UbjMarker) -> UbjResult<()> {
+        // A marker implied by an optimized container takes the place of the next byte,
+        // and it gets consumed (or not) exactly as if it was read from the buffer
+        if let Some(implied) = self.implied_marker {
+            return if implied == marker {
+                self.implied_marker = None;
+                Ok(())
+            } else {
+                Err(UbjError::UnexpectedMarker(implied as u8))
+            };
+        }
+
         let buf = self.buf_refill()?;
         if buf[0] == marker as u8 {
             self.buf_consume(1);
@@ -76,19 +221,150 @@ where
         Ok(joining_buffer)
     }
 
+    fn buf_consume_integer(&mut self) -> UbjResult<i64> {
+        let marker = self.buf_peek_marker()?;
+        match UbjMarker::from_u8(marker) {
+            Some(UbjMarker::Uint8) => self.read_uint8().map(|n| n as i64),
+            Some(UbjMarker::Int8) => self.read_int8().map(|n| n as i64),
+            Some(UbjMarker::Int16) => self.read_int16().map(|n| n as i64),
+            Some(UbjMarker::Int32) => self.read_int32().map(|n| n as i64),
+            Some(UbjMarker::Int64) => self.read_int64(),
+            _ => Err(UbjError::UnexpectedMarker(marker)),
+        }
+    }
+
     #[inline]
     fn buf_consume_usize(&mut self) -> UbjResult<usize> {
-        self.read_uint8()
-            .map(|n| n as usize)
-            .or_else(|_| self.read_int8().map(|n| n as usize))
-            .or_else(|_| self.read_int16().map(|n| n as usize))
-            .or_else(|_| self.read_int32().map(|n| n as usize))
-            .or_else(|_| self.read_int64().map(|n| n as usize))
+        let n = self.buf_consume_integer()?;
+        usize::try_from(n).map_err(|_| UbjError::NegativeLength(n))
+    }
+
+    fn buf_consume_container_header(&mut self) -> UbjResult<UbjContainerHeader> {
+        let mut header = UbjContainerHeader::default();
+        if self.buf_peek_marker()? == UbjMarker::ContainerType as u8 {
+            self.buf_consume_marker(UbjMarker::ContainerType)?;
+            let bytes = self.buf_consume_bytes::<1>()?;
+            let element_type =
+                UbjMarker::from_u8(bytes[0]).ok_or(UbjError::UnexpectedMarker(bytes[0]))?;
+            header.element_type = Some(element_type);
+
+            // The specification mandates the count to follow the type
+            let marker = self.buf_peek_marker()?;
+            if marker != UbjMarker::ContainerCount as u8 {
+                return Err(UbjError::UnexpectedMarker(marker));
+            }
+        }
+        if self.buf_peek_marker()? == UbjMarker::ContainerCount as u8 {
+            self.buf_consume_marker(UbjMarker::ContainerCount)?;
+            header.count = Some(self.buf_consume_usize()?);
+        }
+        Ok(header)
+    }
+
+    fn buf_borrow(&mut self, len: usize) -> UbjResult<Option<&'de [u8]>> {
+        let borrowed = self.underlying.borrow_bytes(len)?;
+        if let Some(bytes) = borrowed {
+            self.bytes_consumed += len;
+            if let Some(raw_capture) = &mut self.raw_capture {
+                raw_capture.extend_from_slice(bytes);
+            }
+        }
+        Ok(borrowed)
+    }
+
+    fn buf_consume_str(&mut self, len: usize) -> UbjResult<Cow<'de, str>> {
+        match self.buf_borrow(len)? {
+            Some(bytes) => Ok(Cow::Borrowed(core::str::from_utf8(bytes)?)),
+            None => self.buf_consume_text(len).map(Cow::Owned),
+        }
+    }
+
+    fn buf_consume_raw(&mut self, len: usize) -> UbjResult<Vec<u8>> {
+        // Create a new joining heap-allocated buffer and a few useful counters. As the length
+        // comes from the input, the buffer grows as bytes actually arrive rather than upfront
+        let mut joining_buffer = Vec::with_capacity(len.min(4096));
+        let mut pending_bytes = len;
+
+        // This is the COPY LOOP
+        while pending_bytes > 0 {
+            // Always refill the source buffer at every iteration
+            let source_buffer = self.buf_refill()?;
+
+            // Derive how many bytes can be copied from the current chunk of the source buffer
+            let current_chunk = source_buffer.len().min(pending_bytes);
+            joining_buffer.extend_from_slice(&source_buffer[..current_chunk]);
+            self.buf_consume(current_chunk);
+
+            // Update the counters accordingly
+            pending_bytes -= current_chunk;
+        }
+        Ok(joining_buffer)
+    }
+
+    fn buf_skip(&mut self, len: usize) -> UbjResult<()> {
+        let mut pending_bytes = len;
+        while pending_bytes > 0 {
+            let current_chunk = self.buf_refill()?.len().min(pending_bytes);
+            self.buf_consume(current_chunk);
+            pending_bytes -= current_chunk;
+        }
+        Ok(())
+    }
+
+    fn buf_skip_elements(
+        &mut self,
+        header: UbjContainerHeader,
+        end: UbjMarker,
+        keyed: bool,
+    ) -> UbjResult<()> {
+        let width = header.element_type.and_then(|t| t.fixed_payload_width());
+        match (width, header.count) {
+            // Fixed-size elements of typed arrays can be jumped over all at once
+            (Some(width), Some(count)) if !keyed => self.buf_skip(count.saturating_mul(width)),
+            (_, Some(count)) => {
+                for _ in 0..count {
+                    self.buf_skip_element(header.element_type, keyed)?;
+                }
+                Ok(())
+            }
+            (_, None) => loop {
+                self.skip_no_ops()?;
+                if self.buf_consume_marker(end).is_ok() {
+                    return Ok(());
+                }
+                self.buf_skip_element(None, keyed)?;
+            },
+        }
+    }
+
+    fn buf_skip_element(&mut self, element_type: Option<UbjMarker>, keyed: bool) -> UbjResult<()> {
+        if keyed {
+            self.skip_no_ops()?;
+            let len = self.buf_consume_usize()?;
+            self.buf_skip(len)?;
+        }
+        // No-op markers may precede any value, but one whose marker is implied
+        self.imply_marker(element_type);
+        self.skip_no_ops()?;
+        self.skip_value()
+    }
+
+    fn buf_consume_byte(&mut self) -> UbjResult<u8> {
+        let marker = self.buf_peek_marker()?;
+        match UbjMarker::from_u8(marker) {
+            Some(UbjMarker::Uint8) => self.read_uint8(),
+            Some(UbjMarker::Int8) => {
+                let n = self.read_int8()?;
+                u8::try_from(n).map_err(|_| UbjError::OutOfRange("u8"))
+            }
+            _ => Err(UbjError::UnexpectedMarker(marker)),
+        }
     }
 
-    fn buf_consume_text(&mut self, len: usize) -> UbjResult<alloc::string::String> {
-        // Create a new joining heap-allocated buffer and a few useful counters
-        let mut joining_buffer = alloc::string::String::with_capacity(len);
+    fn buf_consume_text(&mut self, len: usize) -> UbjResult<String> {
+        // Create a new joining heap-allocated buffer and a few useful counters. As the length
+        // comes from the input, the buffer grows as bytes actually arrive rather than upfront
+        let mut joining_buffer = String::with_capacity(len.min(4096));
         let mut pending_bytes = len;
 
         // This is the COPY LOOP
@@ -161,12 +437,133 @@ where
     // P U B L I C    m e t h o d s
     //
 
+    /// Makes the next value to be read assume the given marker, which it does not carry because
+    /// it is an element (or value) of an optimized container of that type.
+    pub fn imply_marker(&mut self, marker: Option<UbjMarker>) {
+        self.implied_marker = marker;
+    }
+
+    /// Returns the number of bytes consumed so far.
+    pub fn bytes_consumed(&self) -> usize {
+        self.bytes_consumed
+    }
+
+    /// Consumes any no-op markers ahead, and tells whether the end of the input has been reached.
+    pub fn skip_no_ops(&mut self) -> UbjResult<bool> {
+        // The next byte is no marker at all when an optimized container implies it
+        if self.implied_marker.is_some() {
+            return Ok(false);
+        }
+        loop {
+            let buf = self.underlying.fill_buf()?;
+            match buf.first() {
+                None => return Ok(true),
+                Some(&byte) if byte == UbjMarker::NoOp as u8 => self.buf_consume(1),
+                Some(_) => return Ok(false),
+            }
+        }
+    }
+
+    /// Tells whether the end of the input has been reached, without consuming anything.
+    pub fn is_at_end(&mut self) -> UbjResult<bool> {
+        if self.implied_marker.is_some() {
+            return Ok(false);
+        }
+        Ok(self.underlying.fill_buf()?.is_empty())
+    }
+
+    /// Makes sure the input has no bytes left other than no-op markers, which get consumed.
+    pub fn read_end(&mut self) -> UbjResult<()> {
+        if self.skip_no_ops()? {
+            Ok(())
+        } else {
+            Err(UbjError::TrailingBytes(self.bytes_consumed))
+        }
+    }
+
+    /// Peeks the marker of the next value to be read, without consuming it.
+    pub fn peek_marker(&mut self) -> UbjResult<u8> {
+        self.buf_peek_marker()
+    }
+
+    /// Skips the next value, including all values nested within it, without allocating.
+    ///
+    /// Strings, high-precision numbers and optimized containers of fixed-size elements get
+    /// jumped over by their length (or count).
+    pub fn skip_value(&mut self) -> UbjResult<()> {
+        let byte = self.buf_peek_marker()?;
+        let marker = UbjMarker::from_u8(byte).ok_or(UbjError::UnexpectedMarker(byte))?;
+        match marker {
+            UbjMarker::String | UbjMarker::HighPrecision => {
+                self.buf_consume_marker(marker)?;
+                let len = self.buf_consume_usize()?;
+                self.buf_skip(len)
+            }
+            UbjMarker::StartArray => {
+                let header = self.read_start_array()?;
+                self.buf_skip_elements(header, UbjMarker::EndArray, false)
+            }
+            UbjMarker::StartObject => {
+                let header = self.read_start_object()?;
+                self.buf_skip_elements(header, UbjMarker::EndObject, true)
+            }
+            _ => match marker.fixed_payload_width() {
+                Some(width) => {
+                    self.buf_consume_marker(marker)?;
+                    self.buf_skip(width)
+                }
+                None => Err(UbjError::UnexpectedMarker(byte)),
+            },
+        }
+    }
+
+    /// Reads the next value as the exact bytes it is made of, including all values nested within
+    /// it, without decoding them.
+    ///
+    /// A value whose marker is implied by an optimized container gets it prepended, so that the
+    /// bytes returned always make a value on their own.
+    pub fn read_raw_value(&mut self) -> UbjResult<Vec<u8>> {
+        let mut raw_capture = Vec::new();
+        if let Some(implied) = self.implied_marker {
+            raw_capture.push(implied as u8);
+        }
+        self.raw_capture = Some(raw_capture);
+        let result = self.skip_value();
+        let raw_capture = self.raw_capture.take().unwrap_or_default();
+        result.map(|_| raw_capture)
+    }
+
+    /// Reads the next value as the exact bytes it is made of, just like
+    /// [`read_raw_value`](Self::read_raw_value) does, but lending them when the input can.
+    pub fn read_raw(&mut self) -> UbjResult<Cow<'de, [u8]>> {
+        match self.underlying.remaining() {
+            Some(remaining) if self.implied_marker.is_none() => {
+                let start = self.bytes_consumed;
+                self.skip_value()?;
+                Ok(Cow::Borrowed(&remaining[..self.bytes_consumed - start]))
+            }
+            _ => self.read_raw_value().map(Cow::Owned),
+        }
+    }
+
+    /// Reads the given marker alone, such as the marker of a string whose length follows.
+    pub fn read_marker(&mut self, marker: UbjMarker) -> UbjResult<()> {
+        self.buf_consume_marker(marker)
+    }
+
+    /// Reads the UTF-8 text of the given length, borrowing it straight from the input whenever
+    /// possible.
+    pub fn read_str(&mut self, len: usize) -> UbjResult<Cow<'de, str>> {
+        self.buf_consume_str(len)
+    }
+
     pub fn read_bool(&mut self) -> UbjResult<bool> {
-        let bytes = self.buf_consume_bytes::<1>()?;
-        let marker = bytes[0];
+        let marker = self.buf_peek_marker()?;
         if marker == UbjMarker::True as u8 {
+            self.buf_consume_marker(UbjMarker::True)?;
             Ok(true)
         } else if marker == UbjMarker::False as u8 {
+            self.buf_consume_marker(UbjMarker::False)?;
             Ok(false)
         } else {
             Err(UbjError::UnexpectedMarker(marker))
@@ -212,6 +609,11 @@ where
         Ok(value)
     }
 
+    /// Reads an integer of any width (that is one of the `i`, `U`, `I`, `l` and `L` markers).
+    pub fn read_integer(&mut self) -> UbjResult<i64> {
+        self.buf_consume_integer()
+    }
+
     pub fn read_float32(&mut self) -> UbjResult<f32> {
         self.buf_consume_marker(UbjMarker::Float32)?;
         let bytes = self.buf_consume_bytes::<4>()?;
@@ -242,41 +644,70 @@ where
         }
     }
 
-    pub fn read_marked_string(&mut self) -> UbjResult<alloc::string::String> {
-        self.buf_consume_marker(UbjMarker::String)
+    pub fn read_unmarked_string(&mut self) -> UbjResult<String> {
+        let len = self.buf_consume_usize()?;
+        self.buf_consume_text(len)
+    }
+
+    /// Reads a high-precision number, returning its decimal text as is.
+    pub fn read_high_precision(&mut self) -> UbjResult<String> {
+        self.buf_consume_marker(UbjMarker::HighPrecision)
             .and_then(|_| self.read_unmarked_string())
     }
 
-    pub fn read_unmarked_string(&mut self) -> UbjResult<alloc::string::String> {
+    /// Reads a string, borrowing it straight from the input whenever possible.
+    pub fn read_marked_str(&mut self) -> UbjResult<Cow<'de, str>> {
+        self.buf_consume_marker(UbjMarker::String)
+            .and_then(|_| self.read_unmarked_str())
+    }
+
+    /// Reads a string with no marker (such as object keys), borrowing it straight from the input
+    /// whenever possible.
+    pub fn read_unmarked_str(&mut self) -> UbjResult<Cow<'de, str>> {
         let len = self.buf_consume_usize()?;
-        self.buf_consume_text(len)
+        self.buf_consume_str(len)
     }
 
-    // TODO pub fn read_str(&mut self) -> UbjResult<&str> {
-    //     let str_len = self.buf_consume_usize()?;
-    //
-    //     let buf = self.buf_refill()?;
-    //     if buf.len() < str_len {
-    //         return Err(UbjError::BufferTooSmall(
-    //             self.bytes_consumed + str_len + str_len.to_be_bytes().len(),
-    //         ));
-    //     }
-    //
-    //     let source_slice = &buf[..str_len];
-    //     let str_slice = core::str::from_utf8(source_slice)?;
-    //     Ok(str_slice)
-    // }
+    /// Reads an array of bytes, either in the strongly typed `[$U#n` form whose payload gets
+    /// copied in bulk (or borrowed), or as an array of uint8 (or non-negative int8) values.
+    pub fn read_bytes(&mut self) -> UbjResult<Cow<'de, [u8]>> {
+        let header = self.read_start_array()?;
+        match (header.element_type, header.count) {
+            (Some(UbjMarker::Uint8), Some(count)) => match self.buf_borrow(count)? {
+                Some(bytes) => Ok(Cow::Borrowed(bytes)),
+                None => self.buf_consume_raw(count).map(Cow::Owned),
+            },
+            (None | Some(UbjMarker::Int8), Some(count)) => {
+                let mut bytes = Vec::with_capacity(count.min(4096));
+                for _ in 0..count {
+                    self.imply_marker(header.element_type);
+                    bytes.push(self.buf_consume_byte()?);
+                }
+                Ok(Cow::Owned(bytes))
+            }
+            (Some(marker), _) => Err(UbjError::UnexpectedMarker(marker as u8)),
+            (None, None) => {
+                let mut bytes = Vec::new();
+                while self.read_end_array().is_err() {
+                    bytes.push(self.buf_consume_byte()?);
+                }
+                Ok(Cow::Owned(bytes))
+            }
+        }
+    }
 
-    pub fn read_start_array(&mut self) -> UbjResult<()> {
-        self.buf_consume_marker(UbjMarker::StartArray)
+    pub fn read_start_array(&mut self) -> UbjResult<UbjContainerHeader> {
+        self.buf_consume_marker(UbjMarker::StartArray)?;
+        self.buf_consume_container_header()
     }
 
     pub fn read_end_array(&mut self) -> UbjResult<()> {
         self.buf_consume_marker(UbjMarker::EndArray)
     }
 
-    pub fn read_start_object(&mut self) -> UbjResult<()> {
-        self.buf_consume_marker(UbjMarker::StartObject)
+    pub fn read_start_object(&mut self) -> UbjResult<UbjContainerHeader> {
+        self.buf_consume_marker(UbjMarker::StartObject)?;
+        self.buf_consume_container_header()
     }
 
     pub fn read_end_object(&mut self) -> UbjResult<()> {
@@ -1,13 +1,94 @@
 use crate::UbjResult;
 use crate::inner::IoWrite;
 use crate::inner::err::UbjError;
+use crate::inner::markers::UbjMarker;
+use crate::inner::value::{HIGH_PRECISION_TOKEN, RAW_VALUE_TOKEN, is_width_token};
 use crate::inner::writer::UbjWriter;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// Options to tune the Universal Binary JSON produced by the serializer.
+///
+/// The default options make the serializer write containers of known length in the optimized
+/// format, whereas [`UbjSerializerOptions::plain`] makes it produce the plain format only.
+#[derive(Debug, Clone, Copy)]
+pub struct UbjSerializerOptions {
+    optimize_containers: bool,
+    fixed_width_integers: bool,
+    fixed_width_lengths: bool,
+}
+
+impl Default for UbjSerializerOptions {
+    fn default() -> Self {
+        Self {
+            optimize_containers: true,
+            fixed_width_integers: false,
+            fixed_width_lengths: false,
+        }
+    }
+}
+
+impl UbjSerializerOptions {
+    /// Creates new default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new options for the plain (non-optimized) format, where all containers end with
+    /// their closing marker, for the sake of consumers not supporting the optimized one.
+    pub fn plain() -> Self {
+        Self::default().optimize_containers(false)
+    }
+
+    /// Enables (or disables) the optimized format for containers of known length, which is
+    /// enabled by default.
+    ///
+    /// When enabled, sequences, tuples, structs and maps whose length is known in advance are
+    /// written with the `#` count header (instead of the closing marker) and, whenever all their
+    /// elements (or values) turn out to be of the same scalar type, with the `$` type header
+    /// too. Note that the serializer has to buffer such containers in memory to find out.
+    pub fn optimize_containers(mut self, enabled: bool) -> Self {
+        self.optimize_containers = enabled;
+        self
+    }
+
+    /// Enables (or disables) writing integers with the marker of their Rust type, rather than the
+    /// narrowest marker their value fits.
+    ///
+    /// When enabled, `i16` values are written with the `I` marker, `i32` and `u16` values with the
+    /// `l` marker and all wider integers with the `L` marker (unless they do not fit it), so that
+    /// the size of a record does not depend on its values. Integers of optimized containers are
+    /// not widened to a common type either.
+    pub fn fixed_width_integers(mut self, enabled: bool) -> Self {
+        self.fixed_width_integers = enabled;
+        self
+    }
+
+    /// Enables (or disables) writing all lengths (of strings) and counts (of containers) as int32
+    /// values with the `l` marker, rather than the narrowest marker they fit.
+    pub fn fixed_width_lengths(mut self, enabled: bool) -> Self {
+        self.fixed_width_lengths = enabled;
+        self
+    }
+}
 
 pub struct UbjSerializer<W>
 where
     W: IoWrite,
 {
     ubj_writer: UbjWriter<W>,
+    options: UbjSerializerOptions,
+    // Whether integers get written with the marker of their Rust type (rather than the narrowest
+    // marker their value fits), as requested by the options or by the width tokens of Value
+    fixed_width: bool,
+    // Whether the value just written is an integer with the marker given by a width token, which
+    // the optimized container it is within must keep
+    width_token: bool,
+    // Whether bytes are the ones of a raw value, to be copied verbatim
+    raw_value: bool,
+    // The optimized containers being buffered (in the order they started), all of them within
+    // the bytes captured by the writer since the outermost one started
+    buffered: Vec<BufferedContainer>,
 }
 
 impl<W> UbjSerializer<W>
@@ -15,17 +96,69 @@ where
     W: IoWrite,
 {
     fn new(writer: W) -> Self {
+        Self::with_options(writer, UbjSerializerOptions::default())
+    }
+
+    fn with_options(writer: W, options: UbjSerializerOptions) -> Self {
         Self {
-            ubj_writer: UbjWriter::new(writer),
+            ubj_writer: UbjWriter::new(writer).fixed_width_lengths(options.fixed_width_lengths),
+            options,
+            fixed_width: options.fixed_width_integers,
+            width_token: false,
+            raw_value: false,
+            buffered: Vec::new(),
         }
     }
 
     fn flush(&mut self) -> UbjResult<()> {
         self.ubj_writer.flush()
     }
+
+    fn discard_buffered(&mut self) {
+        self.ubj_writer.take_capture();
+        self.buffered.clear();
+    }
+
+    fn start_array(&mut self, len: Option<usize>) -> UbjResult<UbjCompound<'_, W>> {
+        self.start_compound(Container::Array, len, false)
+    }
+
+    fn start_object(&mut self, len: Option<usize>) -> UbjResult<UbjCompound<'_, W>> {
+        self.start_compound(Container::Object, len, false)
+    }
+
+    fn start_compound(
+        &mut self,
+        container: Container,
+        len: Option<usize>,
+        variant: bool,
+    ) -> UbjResult<UbjCompound<'_, W>> {
+        let optimized = len.is_some() && self.options.optimize_containers;
+        if optimized {
+            // Nested containers get buffered right within the outermost one, so that their bytes
+            // get copied only once however deep they are
+            self.ubj_writer.start_capture();
+        }
+        match container {
+            Container::Array => self.ubj_writer.write_start_array()?,
+            Container::Object => self.ubj_writer.write_start_object()?,
+        }
+        let buffer = optimized.then(|| {
+            let start = self.ubj_writer.captured().len();
+            self.buffered
+                .push(BufferedContainer::new(start, len.unwrap_or_default()));
+            self.buffered.len() - 1
+        });
+        Ok(UbjCompound {
+            serializer: self,
+            container,
+            buffer,
+            variant,
+        })
+    }
 }
 
-impl<W> serde::Serializer for &mut UbjSerializer<W>
+impl<'a, W> serde::Serializer for &'a mut UbjSerializer<W>
 where
     W: IoWrite,
 {
@@ -53,40 +186,57 @@ where
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_int16(v)
+        if self.fixed_width {
+            self.ubj_writer.write_fixed_int16(v)
+        } else {
+            self.ubj_writer.write_int16(v)
+        }
     }
 
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_int32(v as i32)
+        self.serialize_i32(v as i32)
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_int32(v)
+        if self.fixed_width {
+            self.ubj_writer.write_fixed_int32(v)
+        } else {
+            self.ubj_writer.write_int32(v)
+        }
     }
 
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_int64(v as i64)
+        self.serialize_i64(v as i64)
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_int64(v)
+        if self.fixed_width {
+            self.ubj_writer.write_fixed_int64(v)
+        } else {
+            self.ubj_writer.write_int64(v)
+        }
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-        if v <= i64::MAX as u64 {
-            return self.ubj_writer.write_int64(v as i64);
+        match i64::try_from(v) {
+            Ok(n) => self.serialize_i64(n),
+            // Values not fitting int64 are written as high-precision numbers
+            Err(_) => self.ubj_writer.write_high_precision(&v.to_string()),
         }
-        Err(UbjError::Unsupported(
-            "Rust u64 values greater than i64::MAX",
-        ))
     }
 
-    fn serialize_i128(self, _v: i128) -> Result<Self::Ok, Self::Error> {
-        Err(UbjError::Unsupported("Rust i128 values"))
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        match i64::try_from(v) {
+            Ok(n) => self.serialize_i64(n),
+            Err(_) => self.ubj_writer.write_high_precision(&v.to_string()),
+        }
     }
 
-    fn serialize_u128(self, _v: u128) -> Result<Self::Ok, Self::Error> {
-        Err(UbjError::Unsupported("Rust u128 values"))
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        match i64::try_from(v) {
+            Ok(n) => self.serialize_i64(n),
+            Err(_) => self.ubj_writer.write_high_precision(&v.to_string()),
+        }
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
@@ -105,21 +255,24 @@ where
         self.ubj_writer.write_marked_string(v)
     }
 
-    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        todo!("serialize_bytes is not yet implemented")
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        if self.raw_value {
+            return self.ubj_writer.write_raw(v);
+        }
+        self.ubj_writer.write_bytes(v)
     }
 
     // ---------------------------------------------------------------------------------
     //  C O M P O U N D   values
     // ---------------------------------------------------------------------------------
 
-    type SerializeSeq = Self;
-    type SerializeTuple = Self;
-    type SerializeTupleStruct = Self;
-    type SerializeTupleVariant = Self;
-    type SerializeMap = Self;
-    type SerializeStruct = Self;
-    type SerializeStructVariant = Self;
+    type SerializeSeq = UbjCompound<'a, W>;
+    type SerializeTuple = UbjCompound<'a, W>;
+    type SerializeTupleStruct = UbjCompound<'a, W>;
+    type SerializeTupleVariant = UbjCompound<'a, W>;
+    type SerializeMap = UbjCompound<'a, W>;
+    type SerializeStruct = UbjCompound<'a, W>;
+    type SerializeStructVariant = UbjCompound<'a, W>;
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         self.ubj_writer.write_null()
@@ -132,14 +285,12 @@ where
         value.serialize(self)
     }
 
-    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        self.ubj_writer.write_start_array()?;
-        Ok(self)
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.start_array(len)
     }
 
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        self.ubj_writer.write_start_array()?;
-        Ok(self)
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.start_array(Some(len))
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
@@ -148,31 +299,51 @@ where
 
     fn serialize_newtype_struct<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T: ?Sized + serde::Serialize,
     {
+        if name == HIGH_PRECISION_TOKEN {
+            // The decimal text of a high-precision number, which follows its marker unmarked
+            self.ubj_writer.write_marker(UbjMarker::HighPrecision)?;
+            return value.serialize(&mut MapKeySerializer {
+                ubj_serializer: self,
+            });
+        }
+        if is_width_token(name) {
+            // An integer to be written with the marker of its Rust type
+            let fixed_width = core::mem::replace(&mut self.fixed_width, true);
+            let result = value.serialize(&mut *self);
+            self.fixed_width = fixed_width;
+            self.width_token = true;
+            return result;
+        }
+        if name == RAW_VALUE_TOKEN {
+            // The bytes of a raw value, to be copied verbatim
+            let raw_value = core::mem::replace(&mut self.raw_value, true);
+            let result = value.serialize(&mut *self);
+            self.raw_value = raw_value;
+            return result;
+        }
         value.serialize(&mut *self)
     }
 
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        self.ubj_writer.write_start_array()?;
-        Ok(self)
+        self.start_array(Some(len))
     }
 
     fn serialize_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        self.ubj_writer.write_start_object()?;
-        Ok(self)
+        self.start_object(Some(len))
     }
 
     fn serialize_unit_variant(
@@ -208,12 +379,11 @@ where
         _name: &'static str,
         _variant_index: u32,
         variant_name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
         self.ubj_writer.write_start_object()?;
         self.ubj_writer.write_unmarked_string(variant_name)?;
-        self.ubj_writer.write_start_array()?;
-        Ok(self)
+        self.start_compound(Container::Array, Some(len), true)
     }
 
     fn serialize_struct_variant(
@@ -221,17 +391,15 @@ where
         _name: &'static str,
         _variant_index: u32,
         variant_name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
         self.ubj_writer.write_start_object()?;
         self.ubj_writer.write_unmarked_string(variant_name)?;
-        self.ubj_writer.write_start_object()?;
-        Ok(self)
+        self.start_compound(Container::Object, Some(len), true)
     }
 
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        self.ubj_writer.write_start_object()?;
-        Ok(self)
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.start_object(len)
     }
 
     #[cfg(not(feature = "std"))]
@@ -243,7 +411,269 @@ where
     }
 }
 
-impl<W> serde::ser::SerializeSeq for &mut UbjSerializer<W>
+// -------------------------------------------------------------------------------------------------
+//  C O M P O U N D S
+// -------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Copy)]
+enum Container {
+    Array,
+    Object,
+}
+
+/// The state of an array (or object) being serialized.
+///
+/// Containers of unknown length (or all containers, when optimization is disabled) are streamed
+/// straight to the underlying writer. Otherwise, they are buffered in memory until the end of the
+/// outermost one so that the serializer can find out which optimized headers to write.
+pub struct UbjCompound<'a, W>
+where
+    W: IoWrite,
+{
+    serializer: &'a mut UbjSerializer<W>,
+    container: Container,
+    // The index of the container among the ones being buffered (if it is)
+    buffer: Option<usize>,
+    // Whether the container is the associated data of an enum variant, that is wrapped
+    // in a single-entry object which has to be closed as well
+    variant: bool,
+}
+
+impl<'a, W> UbjCompound<'a, W>
+where
+    W: IoWrite,
+{
+    fn serialize_key<T>(&mut self, key: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        // The given key can be of any type T that implements the serde::Serialize trait.
+        // Since the type T can be any of the Serde intermediate model types, we need to delegate
+        // to a separate MapKeySerializer unit struct. That implements the serde::Serializer trait
+        // only for those few types that can be turned into valid Universal Binary JSON keys
+        if let Some(index) = self.buffer {
+            let offset = self.serializer.ubj_writer.captured().len();
+            self.serializer.buffered[index].start_entry(offset);
+        }
+        key.serialize(&mut MapKeySerializer {
+            ubj_serializer: &mut *self.serializer,
+        })
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        if let Some(index) = self.buffer {
+            let offset = self.serializer.ubj_writer.captured().len();
+            let buffer = &mut self.serializer.buffered[index];
+            if let Container::Array = self.container {
+                buffer.start_entry(offset);
+            }
+            buffer.start_value(offset);
+        }
+        value.serialize(&mut *self.serializer)?;
+        // Integers with the marker given by a width token (as the ones of Value) must not get
+        // widened to a common type, or they would not come out with the marker they have
+        let width_token = core::mem::take(&mut self.serializer.width_token);
+        if let (Some(index), true) = (self.buffer, width_token) {
+            self.serializer.buffered[index].widen = false;
+        }
+        Ok(())
+    }
+
+    fn end(self) -> UbjResult<()> {
+        let serializer = self.serializer;
+        let writer = &mut serializer.ubj_writer;
+        match (self.buffer, self.container) {
+            (None, Container::Array) => writer.write_end_array()?,
+            (None, Container::Object) => writer.write_end_object()?,
+            (Some(index), _) => {
+                let nested = serializer.buffered.len() - index - 1;
+                let buffer = &mut serializer.buffered[index];
+                buffer.widen &= !serializer.options.fixed_width_integers;
+                buffer.finish(writer.captured(), nested);
+                if index == 0 {
+                    // The outermost container is over, so all bytes captured can be written out
+                    let bytes = writer.take_capture();
+                    let buffered = core::mem::take(&mut serializer.buffered);
+                    writer.write_raw(&bytes[..buffered[0].start])?;
+                    write_buffered(writer, &bytes, &buffered, 0)?;
+                }
+            }
+        }
+        if self.variant {
+            writer.write_end_object()?;
+        }
+        Ok(())
+    }
+}
+
+/// An optimized container being buffered, within the bytes captured since the outermost one.
+struct BufferedContainer {
+    // The offsets where the entries start (right after the opening marker) and where they end
+    start: usize,
+    end: usize,
+    // The offsets where each entry starts and where its (marked) value starts, the entries being
+    // either elements (with their markers) or key-value pairs
+    entries: Vec<(usize, usize)>,
+    // The number of containers buffered within this one, which directly follow it
+    nested: usize,
+    // Whether integers written with different markers may be widened to a common type
+    widen: bool,
+    // The type that all the values have in common (if any), and whether to widen them to it
+    container_type: Option<(UbjMarker, bool)>,
+}
+
+impl BufferedContainer {
+    fn new(start: usize, len: usize) -> Self {
+        Self {
+            start,
+            end: start,
+            entries: Vec::with_capacity(len),
+            nested: 0,
+            widen: true,
+            container_type: None,
+        }
+    }
+
+    fn start_entry(&mut self, offset: usize) {
+        self.entries.push((offset, offset));
+    }
+
+    fn start_value(&mut self, offset: usize) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.1 = offset;
+        }
+    }
+
+    fn finish(&mut self, bytes: &[u8], nested: usize) {
+        self.end = bytes.len();
+        self.nested = nested;
+        self.container_type = self.container_type(bytes);
+    }
+
+    /// Returns the key (if any) and the marked value of the entry at the given index
+    fn entry<'b>(&self, bytes: &'b [u8], index: usize) -> (&'b [u8], &'b [u8]) {
+        let (start, value) = self.entries[index];
+        let end = match self.entries.get(index + 1) {
+            Some((next, _)) => *next,
+            None => self.end,
+        };
+        (&bytes[start..value], &bytes[value..end])
+    }
+
+    /// Finds out the type that all the values have in common (if any) and tells whether they need
+    /// to be widened to such type.
+    ///
+    /// A type is declared only if that makes the container smaller. Integers written with
+    /// different markers can still be widened to a common type, as long as it pays off (and as
+    /// long as widening is allowed).
+    fn container_type(&self, bytes: &[u8]) -> Option<(UbjMarker, bool)> {
+        let count = self.entries.len();
+        if count == 0 {
+            return None;
+        }
+        let first = self.entry(bytes, 0).1[0];
+        let marker = UbjMarker::from_u8(first).filter(|m| m.is_container_type())?;
+
+        // The size of all values (with their markers) as they currently are
+        let marked_size: usize = (0..count).map(|i| self.entry(bytes, i).1.len()).sum();
+
+        if (0..count).all(|i| self.entry(bytes, i).1[0] == first) {
+            // Declaring the type saves one byte per value, but it costs 2 bytes for the header
+            return (count > 2).then_some((marker, false));
+        }
+        if !self.widen {
+            return None;
+        }
+
+        let (mut min, mut max) = (i64::MAX, i64::MIN);
+        for index in 0..count {
+            let value = self.entry(bytes, index).1;
+            UbjMarker::from_u8(value[0]).and_then(|m| m.integer_width())?;
+            let v = decode_integer(value);
+            min = min.min(v);
+            max = max.max(v);
+        }
+        let common = if min >= 0 && max <= u8::MAX as i64 {
+            UbjMarker::Uint8
+        } else if min >= i8::MIN as i64 && max <= i8::MAX as i64 {
+            UbjMarker::Int8
+        } else if min >= i16::MIN as i64 && max <= i16::MAX as i64 {
+            UbjMarker::Int16
+        } else if min >= i32::MIN as i64 && max <= i32::MAX as i64 {
+            UbjMarker::Int32
+        } else {
+            UbjMarker::Int64
+        };
+        let typed_size = 2 + count * common.integer_width().unwrap_or_default();
+        (typed_size < marked_size).then_some((common, true))
+    }
+}
+
+/// Writes the buffered container at the given index, along with all the ones nested within it, and
+/// returns the index of the next one.
+fn write_buffered<W>(
+    writer: &mut UbjWriter<W>,
+    bytes: &[u8],
+    buffered: &[BufferedContainer],
+    index: usize,
+) -> UbjResult<usize>
+where
+    W: IoWrite,
+{
+    let container = &buffered[index];
+    let count = container.entries.len();
+    writer.write_optimized_header(container.container_type.map(|(m, _)| m), count)?;
+    match container.container_type {
+        None => {
+            // The nested containers still miss their headers, which get written in between
+            let mut offset = container.start;
+            let mut next = index + 1;
+            while next <= index + container.nested {
+                writer.write_raw(&bytes[offset..buffered[next].start])?;
+                offset = buffered[next].end;
+                next = write_buffered(writer, bytes, buffered, next)?;
+            }
+            writer.write_raw(&bytes[offset..container.end])?;
+        }
+        Some((marker, widen)) => {
+            // Values of a common type are scalars, so no container is nested within them
+            for entry in 0..count {
+                let (key, value) = container.entry(bytes, entry);
+                writer.write_raw(key)?;
+                if widen {
+                    let v = decode_integer(value);
+                    let width = marker.integer_width().unwrap_or_default();
+                    writer.write_raw(&v.to_be_bytes()[8 - width..])?;
+                } else {
+                    writer.write_raw(&value[1..])?;
+                }
+            }
+        }
+    }
+    Ok(index + 1 + container.nested)
+}
+
+/// Decodes a marked integer value, as written by the UbjWriter
+fn decode_integer(value: &[u8]) -> i64 {
+    match UbjMarker::from_u8(value[0]) {
+        Some(UbjMarker::Int8) => i8::from_be_bytes([value[1]]) as i64,
+        Some(UbjMarker::Uint8) => value[1] as i64,
+        Some(UbjMarker::Int16) => i16::from_be_bytes([value[1], value[2]]) as i64,
+        Some(UbjMarker::Int32) => {
+            i32::from_be_bytes([value[1], value[2], value[3], value[4]]) as i64
+        }
+        _ => {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&value[1..9]);
+            i64::from_be_bytes(bytes)
+        }
+    }
+}
+
+impl<'a, W> serde::ser::SerializeSeq for UbjCompound<'a, W>
 where
     W: IoWrite,
 {
@@ -254,16 +684,15 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        // re-borrow itself
-        value.serialize(&mut (**self))
+        self.serialize_value(value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_end_array()
+        UbjCompound::end(self)
     }
 }
 
-impl<W> serde::ser::SerializeTuple for &mut UbjSerializer<W>
+impl<'a, W> serde::ser::SerializeTuple for UbjCompound<'a, W>
 where
     W: IoWrite,
 {
@@ -274,15 +703,15 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        value.serialize(&mut (**self))
+        self.serialize_value(value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_end_array()
+        UbjCompound::end(self)
     }
 }
 
-impl<W> serde::ser::SerializeTupleStruct for &mut UbjSerializer<W>
+impl<'a, W> serde::ser::SerializeTupleStruct for UbjCompound<'a, W>
 where
     W: IoWrite,
 {
@@ -293,15 +722,15 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        value.serialize(&mut (**self))
+        self.serialize_value(value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_end_array()
+        UbjCompound::end(self)
     }
 }
 
-impl<W> serde::ser::SerializeTupleVariant for &mut UbjSerializer<W>
+impl<'a, W> serde::ser::SerializeTupleVariant for UbjCompound<'a, W>
 where
     W: IoWrite,
 {
@@ -312,16 +741,15 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        value.serialize(&mut (**self))
+        self.serialize_value(value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_end_array()?;
-        self.ubj_writer.write_end_object()
+        UbjCompound::end(self)
     }
 }
 
-impl<W> serde::ser::SerializeStructVariant for &mut UbjSerializer<W>
+impl<'a, W> serde::ser::SerializeStructVariant for UbjCompound<'a, W>
 where
     W: IoWrite,
 {
@@ -332,17 +760,16 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        self.ubj_writer.write_unmarked_string(key)?;
-        value.serialize(&mut (**self))
+        self.serialize_key(key)?;
+        self.serialize_value(value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_end_object()?;
-        self.ubj_writer.write_end_object()
+        UbjCompound::end(self)
     }
 }
 
-impl<W> serde::ser::SerializeStruct for &mut UbjSerializer<W>
+impl<'a, W> serde::ser::SerializeStruct for UbjCompound<'a, W>
 where
     W: IoWrite,
 {
@@ -353,18 +780,18 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        self.ubj_writer.write_unmarked_string(key)?;
-        value.serialize(&mut (**self))
+        self.serialize_key(key)?;
+        self.serialize_value(value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_end_object()
+        UbjCompound::end(self)
     }
 }
 
 // -------------------------------------------------------------------------------------------------
 
-impl<W> serde::ser::SerializeMap for &mut UbjSerializer<W>
+impl<'a, W> serde::ser::SerializeMap for UbjCompound<'a, W>
 where
     W: IoWrite,
 {
@@ -375,21 +802,18 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        let mut map_key_serializer = MapKeySerializer {
-            ubj_serializer: self,
-        };
-        key.serialize(&mut map_key_serializer)
+        UbjCompound::serialize_key(self, key)
     }
 
     fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
         T: ?Sized + serde::Serialize,
     {
-        value.serialize(&mut (**self))
+        UbjCompound::serialize_value(self, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.ubj_writer.write_end_object()
+        UbjCompound::end(self)
     }
 }
 
@@ -586,6 +1010,171 @@ where
 
 // -------------------------------------------------------------------------------------------------
 
+/// An incremental writer of a top-level array, whose elements get serialized one at a time as
+/// they are pushed (so that the whole collection never needs to be in memory).
+///
+/// The closing `]` marker gets written by [`SeqWriter::finish`] or, when enabled by
+/// [`SeqWriter::finish_on_drop`], when the writer is dropped.
+///
+/// # Examples
+/// ```rust
+/// fn main() -> Result<(), serde_ubj::UbjError> {
+///     let mut bytes = Vec::new();
+///     let mut seq = serde_ubj::SeqWriter::new(&mut bytes)?;
+///     seq.push(&7_i8)?;
+///     seq.push("a")?;
+///     seq.finish()?;
+///
+///     //                  [[]   [i]   [7]   [S]   [U]   [1]   [a]   []]
+///     assert_eq!(bytes, [0x5B, 0x69, 0x07, 0x53, 0x55, 0x01, 0x61, 0x5D]);
+///     Ok(())
+/// }
+/// ```
+pub struct SeqWriter<W>
+where
+    W: IoWrite,
+{
+    serializer: UbjSerializer<W>,
+    finished: bool,
+    finish_on_drop: bool,
+}
+
+impl<W> SeqWriter<W>
+where
+    W: IoWrite,
+{
+    /// Creates a new writer of an array to the given IO writer, writing its opening `[` marker.
+    pub fn new(writer: W) -> UbjResult<Self> {
+        Self::with_options(writer, UbjSerializerOptions::default())
+    }
+
+    /// Creates a new writer of an array to the given IO writer, writing its opening `[` marker,
+    /// whose elements get serialized as tuned by the given options.
+    pub fn with_options(writer: W, options: UbjSerializerOptions) -> UbjResult<Self> {
+        let mut seq = Self::resume(writer, options);
+        seq.serializer.ubj_writer.write_start_array()?;
+        Ok(seq)
+    }
+
+    // Continues an array whose opening marker has been written already
+    fn resume(writer: W, options: UbjSerializerOptions) -> Self {
+        Self {
+            serializer: UbjSerializer::with_options(writer, options),
+            finished: false,
+            finish_on_drop: false,
+        }
+    }
+
+    /// Enables (or disables) writing the closing `]` marker when this writer gets dropped
+    /// without having been finished. Errors occurring at that time are ignored.
+    pub fn finish_on_drop(mut self, enabled: bool) -> Self {
+        self.finish_on_drop = enabled;
+        self
+    }
+
+    /// Serializes the given value as the next element of the array.
+    pub fn push<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let result = value.serialize(&mut self.serializer);
+        if result.is_err() {
+            // The element may have failed within an optimized container, still being buffered
+            self.serializer.discard_buffered();
+        }
+        result
+    }
+
+    /// Flushes the underlying writer, so that all elements pushed so far reach it.
+    pub fn flush(&mut self) -> UbjResult<()> {
+        self.serializer.flush()
+    }
+
+    /// Writes the closing `]` marker of the array and flushes the underlying writer.
+    pub fn finish(mut self) -> UbjResult<()> {
+        self.finished = true;
+        self.serializer.ubj_writer.write_end_array()?;
+        self.serializer.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W> SeqWriter<W>
+where
+    W: std::io::Read + std::io::Write + std::io::Seek,
+{
+    /// Reopens an array previously written (and finished) to the given file, so that more
+    /// elements can be appended to it. The closing `]` marker at the very end of the file gets
+    /// overwritten by the next element (and then written again when finishing).
+    ///
+    /// An empty file gets a new array instead, whereas a file not starting with a plain `[`
+    /// (rather than an optimized one, whose `#` count could not grow) or not ending with `]` is an
+    /// error.
+    ///
+    /// Note that elements get written straight to the given file, which should therefore be
+    /// buffered if desired (as long as it can still be read and sought).
+    pub fn append(writer: W) -> UbjResult<Self> {
+        Self::append_with_options(writer, UbjSerializerOptions::default())
+    }
+
+    /// Reopens an array previously written (and finished) to the given file, as tuned by the
+    /// given options (see [`SeqWriter::append`]).
+    pub fn append_with_options(mut writer: W, options: UbjSerializerOptions) -> UbjResult<Self> {
+        use std::io::SeekFrom;
+        let len = writer
+            .seek(SeekFrom::End(0))
+            .map_err(UbjError::from_io_error)?;
+        if len == 0 {
+            return Self::with_options(writer, options);
+        }
+        // The header of the array, which must be plain (a `$` type also comes with a `#` count)
+        let mut header = [0_u8; 2];
+        writer
+            .seek(SeekFrom::Start(0))
+            .map_err(UbjError::from_io_error)?;
+        writer
+            .read_exact(&mut header[..len.min(2) as usize])
+            .map_err(UbjError::from_io_error)?;
+        if header[0] != UbjMarker::StartArray as u8 {
+            return Err(UbjError::UnexpectedMarker(header[0]));
+        }
+        if header[1] == UbjMarker::ContainerType as u8
+            || header[1] == UbjMarker::ContainerCount as u8
+        {
+            return Err(UbjError::UnexpectedMarker(header[1]));
+        }
+
+        let mut last = [0_u8; 1];
+        writer
+            .seek(SeekFrom::End(-1))
+            .map_err(UbjError::from_io_error)?;
+        writer
+            .read_exact(&mut last)
+            .map_err(UbjError::from_io_error)?;
+        if len < 2 || last[0] != UbjMarker::EndArray as u8 {
+            return Err(UbjError::UnexpectedMarker(last[0]));
+        }
+        writer
+            .seek(SeekFrom::End(-1))
+            .map_err(UbjError::from_io_error)?;
+        Ok(Self::resume(writer, options))
+    }
+}
+
+impl<W> Drop for SeqWriter<W>
+where
+    W: IoWrite,
+{
+    fn drop(&mut self) {
+        if self.finish_on_drop && !self.finished {
+            let _ = self.serializer.ubj_writer.write_end_array();
+            let _ = self.serializer.flush();
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Serializes a Rust value of type `T` to an IO writer.
 ///
 /// Be aware that, to avoid accidental double buffering, this function does **not** wrap the provided
@@ -614,13 +1203,45 @@ where
 pub fn to_writer<W, T>(writer: &mut W, value: &T) -> Result<(), UbjError>
 where
     W: IoWrite,
-    T: serde::Serialize
+    T: serde::Serialize,
 {
     let mut serializer = UbjSerializer::new(writer);
     value.serialize(&mut serializer)?;
     serializer.flush()
 }
 
+/// Serializes a Rust value of type `T` to an IO writer, as tuned by the given options.
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::UbjSerializerOptions;
+///
+/// fn main() -> Result<(), serde_ubj::UbjError> {
+///     let value = vec![1.5_f32, 2.5_f32, 3.5_f32];
+///     let options = UbjSerializerOptions::plain();
+///
+///     let mut writer = Vec::new();
+///     serde_ubj::to_writer_with_options(&mut writer, &value, options)?;
+///
+///     // [[] followed by each element (with its [d] marker) and the closing []]
+///     assert_eq!(&writer[..2], &[0x5B, 0x64]);
+///     assert_eq!(writer.len(), 1 + 3 * 5 + 1);
+///     Ok(())
+/// }
+/// ```
+pub fn to_writer_with_options<W, T>(
+    writer: &mut W,
+    value: &T,
+    options: UbjSerializerOptions,
+) -> Result<(), UbjError>
+where
+    W: IoWrite,
+    T: serde::Serialize,
+{
+    let mut serializer = UbjSerializer::with_options(writer, options);
+    value.serialize(&mut serializer)?;
+    serializer.flush()
+}
 
 /// Serializes a Rust value of type `T` to a vector (in-memory buffer) of bytes.
 /// # Examples
@@ -645,3 +1266,14 @@ where
     to_writer(&mut vec, value)?;
     Ok(vec)
 }
+
+/// Serializes a Rust value of type `T` to a vector (in-memory buffer) of bytes, as tuned by the
+/// given options.
+pub fn to_vec_with_options<T>(value: &T, options: UbjSerializerOptions) -> Result<Vec<u8>, UbjError>
+where
+    T: serde::Serialize,
+{
+    let mut vec = Vec::new();
+    to_writer_with_options(&mut vec, value, options)?;
+    Ok(vec)
+}
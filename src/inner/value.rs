@@ -0,0 +1,801 @@
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
+    VariantAccess, Visitor,
+};
+use serde::ser::{SerializeMap, SerializeSeq};
+
+// The name of the newtype struct a high-precision number gets serialized as, so that our
+// serializer can tell it apart from a string and write it with the `H` marker
+pub(crate) const HIGH_PRECISION_TOKEN: &str = "$serde_ubj::private::HighPrecision";
+
+// The names of the newtype structs integers get serialized as, so that our serializers can write
+// them with the very marker they have (rather than the narrowest marker their value fits)
+pub(crate) const INT16_TOKEN: &str = "$serde_ubj::private::Int16";
+pub(crate) const INT32_TOKEN: &str = "$serde_ubj::private::Int32";
+pub(crate) const INT64_TOKEN: &str = "$serde_ubj::private::Int64";
+
+pub(crate) fn is_width_token(name: &str) -> bool {
+    name == INT16_TOKEN || name == INT32_TOKEN || name == INT64_TOKEN
+}
+
+// The name of the newtype struct a value gets deserialized as, so that our deserializer can tell
+// the visitor about high-precision numbers (which would otherwise be visited as integers or text)
+pub(crate) const VALUE_TOKEN: &str = "$serde_ubj::private::Value";
+
+// The name of the newtype struct a raw value gets (de)serialized as, so that our serializer can
+// copy its bytes verbatim and our deserializer can hand the bytes of a whole value over
+pub(crate) const RAW_VALUE_TOKEN: &str = "$serde_ubj::private::RawValue";
+
+/// Tells a visitor about a high-precision number as a newtype variant named after the token, as
+/// no self-describing input ever gets visited as an enum (whereas any input may have such a key).
+pub(crate) struct HighPrecisionAccess<E> {
+    text: String,
+    error: PhantomData<E>,
+}
+
+impl<E> HighPrecisionAccess<E> {
+    pub(crate) fn new(text: String) -> Self {
+        Self {
+            text,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<'de, E> EnumAccess<'de> for HighPrecisionAccess<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), E>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(HIGH_PRECISION_TOKEN.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, E> VariantAccess<'de> for HighPrecisionAccess<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), E> {
+        Err(E::invalid_type(Unexpected::NewtypeVariant, &"unit variant"))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, E>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.text.into_deserializer())
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        Err(E::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        Err(E::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+/// Any Universal Binary JSON value, whose variants mirror the types the format is made of.
+///
+/// It comes in handy to deal with values whose schema is not known ahead of time. Note that
+/// integers keep the width they have been read with (and get written back with it), and that the
+/// `Display` implementation renders the value as JSON text.
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::Value;
+///
+/// //           [{]   [U]   [1]   [a]   [[]   [i]   [7]   [I]   [1]   [0]   []]   [}]
+/// let bytes = [0x7B, 0x55, 0x01, 0x61, 0x5B, 0x69, 0x07, 0x49, 0x01, 0x00, 0x5D, 0x7D];
+/// let value: Value = serde_ubj::from_slice(&bytes).unwrap();
+/// assert_eq!(value["a"][1], Value::Int16(256));
+/// assert_eq!(value.to_string(), r#"{"a":[7,256]}"#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Value {
+    /// The `Z` value
+    #[default]
+    Null,
+    /// The `T` or `F` value
+    Bool(bool),
+    /// An `i` integer
+    Int8(i8),
+    /// A `U` integer
+    Uint8(u8),
+    /// An `I` integer
+    Int16(i16),
+    /// An `l` integer
+    Int32(i32),
+    /// An `L` integer
+    Int64(i64),
+    /// A `d` floating point number
+    Float32(f32),
+    /// A `D` floating point number
+    Float64(f64),
+    /// A `C` character (within the ASCII range)
+    Char(char),
+    /// An `S` string
+    String(String),
+    /// An `H` number, as its decimal text
+    HighPrecision(String),
+    /// An array of values
+    Array(Vec<Value>),
+    /// An object, made of values by their keys
+    Object(Map),
+}
+
+static NULL: Value = Value::Null;
+
+impl Value {
+    /// Tells whether this is the null value.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Returns the boolean, if this is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer as `i64`, if this is an integer (of any width) that fits it.
+    ///
+    /// High-precision numbers are parsed, as long as they are integers that fit.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int8(v) => Some(*v as i64),
+            Value::Uint8(v) => Some(*v as i64),
+            Value::Int16(v) => Some(*v as i64),
+            Value::Int32(v) => Some(*v as i64),
+            Value::Int64(v) => Some(*v),
+            Value::HighPrecision(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer as `u64`, if this is an integer (of any width) that fits it.
+    ///
+    /// High-precision numbers are parsed, as long as they are integers that fit.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::HighPrecision(text) => text.parse().ok(),
+            _ => self.as_i64().and_then(|v| u64::try_from(v).ok()),
+        }
+    }
+
+    /// Returns the number as `f64`, if this is a number of any kind (possibly losing precision).
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float32(v) => Some(*v as f64),
+            Value::Float64(v) => Some(*v),
+            Value::HighPrecision(text) => text.parse().ok(),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+
+    /// Returns the character, if this is one.
+    pub fn as_char(&self) -> Option<char> {
+        match self {
+            Value::Char(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the string, if this is one.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the decimal text of the high-precision number, if this is one.
+    pub fn as_high_precision(&self) -> Option<&str> {
+        match self {
+            Value::HighPrecision(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements, if this is an array.
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the mutable elements, if this is an array.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the entries, if this is an object.
+    pub fn as_object(&self) -> Option<&Map> {
+        match self {
+            Value::Object(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the mutable entries, if this is an object.
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Value::Object(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//  I N D E X I N G
+// -------------------------------------------------------------------------------------------------
+
+/// Looking up an element that is not there (or of a value that is not an array) gives null.
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Value {
+        self.as_array().and_then(|a| a.get(index)).unwrap_or(&NULL)
+    }
+}
+
+/// Panics if this is not an array, or if the index is out of bounds.
+impl IndexMut<usize> for Value {
+    fn index_mut(&mut self, index: usize) -> &mut Value {
+        match self {
+            Value::Array(a) => &mut a[index],
+            _ => panic!("cannot index into a non-array value"),
+        }
+    }
+}
+
+/// Looking up a key that is not there (or of a value that is not an object) gives null.
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.as_object().and_then(|o| o.get(key)).unwrap_or(&NULL)
+    }
+}
+
+/// Inserts null for a key that is not there yet, whereas it panics if this is not an object.
+impl IndexMut<&str> for Value {
+    fn index_mut(&mut self, key: &str) -> &mut Value {
+        match self {
+            Value::Object(o) => o.entry(key),
+            _ => panic!("cannot index into a non-object value"),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//  C O N V E R S I O N S
+// -------------------------------------------------------------------------------------------------
+
+macro_rules! impl_from {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$t> for Value {
+                fn from(v: $t) -> Self {
+                    Value::$variant(v.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    bool => Bool,
+    i8 => Int8,
+    u8 => Uint8,
+    i16 => Int16,
+    i32 => Int32,
+    i64 => Int64,
+    u16 => Int32,
+    u32 => Int64,
+    f32 => Float32,
+    f64 => Float64,
+    char => Char,
+    String => String,
+    &str => String,
+    Vec<Value> => Array,
+    Map => Object,
+}
+
+/// Integers beyond the `i64` range become high-precision numbers.
+impl From<u64> for Value {
+    fn from(v: u64) -> Self {
+        match i64::try_from(v) {
+            Ok(v) => Value::Int64(v),
+            Err(_) => Value::HighPrecision(v.to_string()),
+        }
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::Null
+    }
+}
+
+impl<T> From<Option<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T> FromIterator<T> for Value
+where
+    T: Into<Value>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//  J S O N   text
+// -------------------------------------------------------------------------------------------------
+
+/// Renders the value as (compact) JSON text.
+///
+/// Characters become strings, high-precision numbers are written as their decimal text, and
+/// floating point numbers which are not finite become null (as JSON has no way to tell them).
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::Int8(v) => write!(f, "{v}"),
+            Value::Uint8(v) => write!(f, "{v}"),
+            Value::Int16(v) => write!(f, "{v}"),
+            Value::Int32(v) => write!(f, "{v}"),
+            Value::Int64(v) => write!(f, "{v}"),
+            Value::Float32(v) if v.is_finite() => write!(f, "{v}"),
+            Value::Float64(v) if v.is_finite() => write!(f, "{v}"),
+            Value::Float32(_) | Value::Float64(_) => f.write_str("null"),
+            Value::Char(v) => write_json_string(f, v.encode_utf8(&mut [0; 4])),
+            Value::String(v) => write_json_string(f, v),
+            Value::HighPrecision(v) => f.write_str(v),
+            Value::Array(elements) => {
+                f.write_str("[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                f.write_str("]")
+            }
+            Value::Object(entries) => {
+                f.write_str("{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_json_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_json_string(f: &mut fmt::Formatter<'_>, v: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in v.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+// -------------------------------------------------------------------------------------------------
+//  S E R D E
+// -------------------------------------------------------------------------------------------------
+
+impl serde::Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::Int8(v) => serializer.serialize_i8(*v),
+            Value::Uint8(v) => serializer.serialize_u8(*v),
+            Value::Int16(v) => serializer.serialize_newtype_struct(INT16_TOKEN, v),
+            Value::Int32(v) => serializer.serialize_newtype_struct(INT32_TOKEN, v),
+            Value::Int64(v) => serializer.serialize_newtype_struct(INT64_TOKEN, v),
+            Value::Float32(v) => serializer.serialize_f32(*v),
+            Value::Float64(v) => serializer.serialize_f64(*v),
+            Value::Char(v) => serializer.serialize_char(*v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::HighPrecision(v) => {
+                serializer.serialize_newtype_struct(HIGH_PRECISION_TOKEN, v.as_str())
+            }
+            Value::Array(elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            Value::Object(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, ValueVisitor)
+    }
+}
+
+pub(crate) struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any Universal Binary JSON value")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i8<E>(self, v: i8) -> Result<Value, E> {
+        Ok(Value::Int8(v))
+    }
+
+    fn visit_u8<E>(self, v: u8) -> Result<Value, E> {
+        Ok(Value::Uint8(v))
+    }
+
+    fn visit_i16<E>(self, v: i16) -> Result<Value, E> {
+        Ok(Value::Int16(v))
+    }
+
+    fn visit_i32<E>(self, v: i32) -> Result<Value, E> {
+        Ok(Value::Int32(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Int64(v))
+    }
+
+    fn visit_u16<E>(self, v: u16) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u32<E>(self, v: u32) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Value, E> {
+        Ok(match i64::try_from(v) {
+            Ok(v) => Value::Int64(v),
+            Err(_) => Value::HighPrecision(v.to_string()),
+        })
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Value, E> {
+        Ok(match i64::try_from(v) {
+            Ok(v) => Value::Int64(v),
+            Err(_) => Value::HighPrecision(v.to_string()),
+        })
+    }
+
+    fn visit_f32<E>(self, v: f32) -> Result<Value, E> {
+        Ok(Value::Float32(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Float64(v))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Value, E> {
+        Ok(Value::Char(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(Value::Array(elements))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value()?;
+            entries.insert(key, value);
+        }
+        Ok(Value::Object(entries))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        // Our deserializers tell about a high-precision number this way
+        let (variant, access) = data.variant::<String>()?;
+        if variant == HIGH_PRECISION_TOKEN {
+            return access.newtype_variant().map(Value::HighPrecision);
+        }
+        Err(de::Error::invalid_type(Unexpected::Enum, &self))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+//  M A P
+// -------------------------------------------------------------------------------------------------
+
+/// The entries of an object value, which keep the order they have been inserted in.
+///
+/// Keys are looked up by means of an index kept next to the entries, so that building an object
+/// out of many entries takes no longer than building a sorted map out of them.
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: Vec<(String, Value)>,
+    // The position of the entry of each key
+    index: BTreeMap<String, usize>,
+}
+
+impl Map {
+    /// Creates a new empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Tells whether there are no entries at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of the given key, if any.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.index.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    /// Returns the position of the entry of the given key along with its value, if any.
+    pub(crate) fn get_full(&self, key: &str) -> Option<(usize, &Value)> {
+        self.index.get(key).map(|&i| (i, &self.entries[i].1))
+    }
+
+    /// Returns the mutable value of the given key, if any.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.index.get(key).map(|&i| &mut self.entries[i].1)
+    }
+
+    /// Tells whether there is an entry of the given key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Inserts the given value for the given key, returning the previous value (if any).
+    ///
+    /// A key already there keeps its position, whereas a new key is added at the end.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        match self.get_mut(&key) {
+            Some(v) => Some(core::mem::replace(v, value)),
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the entry of the given key, returning its value (if any).
+    ///
+    /// The entries after it get shifted back by one, so that they keep their order.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let removed = self.index.remove(key)?;
+        for i in self.index.values_mut() {
+            if *i > removed {
+                *i -= 1;
+            }
+        }
+        Some(self.entries.remove(removed).1)
+    }
+
+    /// Returns the mutable value of the given key, after inserting null for it if not there yet.
+    pub fn entry(&mut self, key: &str) -> &mut Value {
+        let i = match self.index.get(key) {
+            Some(&i) => i,
+            None => {
+                self.insert(key.to_owned(), Value::Null);
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[i].1
+    }
+
+    /// Returns an iterator over the entries, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Returns an iterator over the entries with mutable values, in order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut Value)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    /// Returns an iterator over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Returns an iterator over the values, in order.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+/// Two maps are equal when they have the same entries in the same order.
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl Index<&str> for Map {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = core::iter::Map<
+        core::slice::Iter<'a, (String, Value)>,
+        fn(&'a (String, Value)) -> (&'a String, &'a Value),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Value);
+    type IntoIter = alloc::vec::IntoIter<(String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<K> FromIterator<(K, Value)> for Map
+where
+    K: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, Value)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K> Extend<(K, Value)> for Map
+where
+    K: Into<String>,
+{
+    fn extend<I: IntoIterator<Item = (K, Value)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key.into(), value);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use crate::inner::de::visit_high_precision;
+use crate::inner::err::{UbjError, UbjResult};
+use crate::inner::ser::to_vec;
+use crate::inner::value::{HighPrecisionAccess, Map, RAW_VALUE_TOKEN, VALUE_TOKEN, Value};
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::de::value::{MapDeserializer, SeqDeserializer, StringDeserializer};
+use serde::de::{Error, IntoDeserializer, Unexpected, Visitor};
+
+impl Value {
+    pub(crate) fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Value::Null => Unexpected::Unit,
+            Value::Bool(v) => Unexpected::Bool(*v),
+            Value::Int8(v) => Unexpected::Signed(*v as i64),
+            Value::Uint8(v) => Unexpected::Unsigned(*v as u64),
+            Value::Int16(v) => Unexpected::Signed(*v as i64),
+            Value::Int32(v) => Unexpected::Signed(*v as i64),
+            Value::Int64(v) => Unexpected::Signed(*v),
+            Value::Float32(v) => Unexpected::Float(*v as f64),
+            Value::Float64(v) => Unexpected::Float(*v),
+            Value::Char(v) => Unexpected::Char(*v),
+            Value::String(v) => Unexpected::Str(v),
+            Value::HighPrecision(_) => Unexpected::Other("high-precision number"),
+            Value::Array(_) => Unexpected::Seq,
+            Value::Object(_) => Unexpected::Map,
+        }
+    }
+}
+
+fn visit_array<'de, V>(elements: Vec<Value>, visitor: V) -> UbjResult<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let mut seq = SeqDeserializer::new(elements.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn visit_object<'de, V>(entries: Map, visitor: V) -> UbjResult<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let mut map = MapDeserializer::new(entries.into_iter());
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+/// A value can be deserialized into any Rust type `T` whose structure it matches, the very same
+/// way the bytes it would be serialized to could be.
+impl<'de> serde::Deserializer<'de> for Value {
+    type Error = UbjError;
+
+    fn deserialize_any<V>(self, visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Int8(v) => visitor.visit_i8(v),
+            Value::Uint8(v) => visitor.visit_u8(v),
+            Value::Int16(v) => visitor.visit_i16(v),
+            Value::Int32(v) => visitor.visit_i32(v),
+            Value::Int64(v) => visitor.visit_i64(v),
+            Value::Float32(v) => visitor.visit_f32(v),
+            Value::Float64(v) => visitor.visit_f64(v),
+            Value::Char(v) => visitor.visit_char(v),
+            Value::String(v) => visitor.visit_string(v),
+            // Just like the bytes of a high-precision number would be visited
+            Value::HighPrecision(text) => visit_high_precision(text, visitor),
+            Value::Array(elements) => visit_array(elements, visitor),
+            Value::Object(entries) => visit_object(entries, visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Array(elements) if elements.iter().all(|e| matches!(e, Value::Uint8(_))) => {
+                let bytes = elements
+                    .iter()
+                    .filter_map(|e| match e {
+                        Value::Uint8(b) => Some(*b),
+                        _ => None,
+                    })
+                    .collect();
+                visitor.visit_byte_buf(bytes)
+            }
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            // Tell the Value visitor about a high-precision number, as our deserializer does
+            Value::HighPrecision(text) if name == VALUE_TOKEN => {
+                visitor.visit_enum(HighPrecisionAccess::new(text))
+            }
+            value if name == VALUE_TOKEN => value.deserialize_any(visitor),
+            value if name == RAW_VALUE_TOKEN => visitor.visit_byte_buf(to_vec(&value)?),
+            value => visitor.visit_newtype_struct(value),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // Enum variants are objects with one single entry, keyed by the variant name
+        match self {
+            Value::Object(entries) if entries.len() == 1 => {
+                let (variant, value) = entries.into_iter().next().unwrap_or_default();
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
+            other => Err(UbjError::invalid_type(other.unexpected(), &"enum variant")),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, UbjError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Value,
+}
+
+impl<'de> serde::de::EnumAccess<'de> for EnumDeserializer {
+    type Error = UbjError;
+    type Variant = Value;
+
+    fn variant_seed<V>(self, seed: V) -> UbjResult<(V::Value, Value)>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let variant: StringDeserializer<UbjError> = self.variant.into_deserializer();
+        Ok((seed.deserialize(variant)?, self.value))
+    }
+}
+
+impl<'de> serde::de::VariantAccess<'de> for Value {
+    type Error = UbjError;
+
+    fn unit_variant(self) -> UbjResult<()> {
+        match self {
+            Value::Null => Ok(()),
+            other => Err(UbjError::invalid_type(other.unexpected(), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> UbjResult<T::Value>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Array(elements) => visit_array(elements, visitor),
+            other => Err(UbjError::invalid_type(other.unexpected(), &"tuple variant")),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> UbjResult<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Object(entries) => visit_object(entries, visitor),
+            other => Err(UbjError::invalid_type(
+                other.unexpected(),
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Converts a [`Value`] tree to a Rust value of type `T`, without going through bytes.
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::Value;
+///
+/// let value = Value::Array(vec![Value::Int8(7), Value::Int16(300)]);
+/// let pair: (i64, u16) = serde_ubj::from_value(value).unwrap();
+/// assert_eq!(pair, (7, 300));
+/// ```
+pub fn from_value<T>(value: Value) -> UbjResult<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    T::deserialize(value)
+}
@@ -0,0 +1,458 @@
+use crate::inner::de::from_slice;
+use crate::inner::err::{UbjError, UbjResult};
+use crate::inner::value::{
+    HIGH_PRECISION_TOKEN, INT16_TOKEN, INT32_TOKEN, INT64_TOKEN, Map, RAW_VALUE_TOKEN, Value,
+};
+use crate::inner::value_de::from_value;
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// A serializer that builds a [`Value`] tree, instead of writing bytes.
+///
+/// The value it builds mirrors what the Universal Binary JSON serializer would write (with
+/// default options): integers get the narrowest marker their value fits, and enum variants become
+/// objects keyed by the variant name. Byte arrays become arrays of `U` integers, which lose their
+/// optimized format though.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValueSerializer;
+
+/// Returns the value of the narrowest integer marker the given integer fits, as the Universal
+/// Binary JSON serializer would write it.
+fn narrowest(v: i64) -> Value {
+    if (i8::MIN as i64..0).contains(&v) {
+        Value::Int8(v as i8)
+    } else if (0..=u8::MAX as i64).contains(&v) {
+        Value::Uint8(v as u8)
+    } else if let Ok(v) = i16::try_from(v) {
+        Value::Int16(v)
+    } else if let Ok(v) = i32::try_from(v) {
+        Value::Int32(v)
+    } else {
+        Value::Int64(v)
+    }
+}
+
+/// Tells the type of a value which is not allowed to be a key of an object.
+fn illegal_key_type(value: &Value) -> UbjError {
+    UbjError::IllegalKeyType(match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Float32(_) | Value::Float64(_) => "float",
+        Value::Char(_) => "char",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        _ => "integer",
+    })
+}
+
+impl serde::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = UbjError;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, v: bool) -> UbjResult<Value> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> UbjResult<Value> {
+        Ok(Value::Int8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> UbjResult<Value> {
+        Ok(narrowest(v as i64))
+    }
+
+    fn serialize_i32(self, v: i32) -> UbjResult<Value> {
+        Ok(narrowest(v as i64))
+    }
+
+    fn serialize_i64(self, v: i64) -> UbjResult<Value> {
+        Ok(narrowest(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> UbjResult<Value> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(narrowest(v)),
+            Err(_) => Ok(Value::HighPrecision(v.to_string())),
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> UbjResult<Value> {
+        Ok(Value::Uint8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> UbjResult<Value> {
+        Ok(narrowest(v as i64))
+    }
+
+    fn serialize_u32(self, v: u32) -> UbjResult<Value> {
+        Ok(narrowest(v as i64))
+    }
+
+    fn serialize_u64(self, v: u64) -> UbjResult<Value> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(narrowest(v)),
+            Err(_) => Ok(Value::HighPrecision(v.to_string())),
+        }
+    }
+
+    fn serialize_u128(self, v: u128) -> UbjResult<Value> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(narrowest(v)),
+            Err(_) => Ok(Value::HighPrecision(v.to_string())),
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> UbjResult<Value> {
+        Ok(Value::Float32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> UbjResult<Value> {
+        Ok(Value::Float64(v))
+    }
+
+    fn serialize_char(self, v: char) -> UbjResult<Value> {
+        if v.is_ascii() {
+            Ok(Value::Char(v))
+        } else {
+            Err(UbjError::CharNotAscii(v as u32))
+        }
+    }
+
+    fn serialize_str(self, v: &str) -> UbjResult<Value> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> UbjResult<Value> {
+        Ok(Value::Array(v.iter().map(|b| Value::Uint8(*b)).collect()))
+    }
+
+    fn serialize_none(self) -> UbjResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> UbjResult<Value>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> UbjResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> UbjResult<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+    ) -> UbjResult<Value> {
+        Ok(variant(variant_name, Value::Null))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> UbjResult<Value>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let value = value.serialize(self)?;
+        if name == RAW_VALUE_TOKEN {
+            // The bytes of a raw value, which get decoded into the value they are made of
+            let bytes: Vec<u8> = from_value(value)?;
+            return from_slice(&bytes);
+        }
+        match (name, value.as_i64()) {
+            (INT16_TOKEN, Some(v)) => Ok(Value::Int16(v as i16)),
+            (INT32_TOKEN, Some(v)) => Ok(Value::Int32(v as i32)),
+            (INT64_TOKEN, Some(v)) => Ok(Value::Int64(v)),
+            _ => match value {
+                Value::String(text) if name == HIGH_PRECISION_TOKEN => {
+                    Ok(Value::HighPrecision(text))
+                }
+                value => Ok(value),
+            },
+        }
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        value: &T,
+    ) -> UbjResult<Value>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        Ok(variant(variant_name, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> UbjResult<SerializeArray> {
+        Ok(SerializeArray {
+            variant_name: None,
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> UbjResult<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> UbjResult<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        len: usize,
+    ) -> UbjResult<SerializeArray> {
+        Ok(SerializeArray {
+            variant_name: Some(variant_name),
+            elements: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> UbjResult<SerializeObject> {
+        Ok(SerializeObject {
+            variant_name: None,
+            entries: Map::new(),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> UbjResult<SerializeObject> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        _len: usize,
+    ) -> UbjResult<SerializeObject> {
+        Ok(SerializeObject {
+            variant_name: Some(variant_name),
+            entries: Map::new(),
+            next_key: None,
+        })
+    }
+}
+
+/// Returns the object an enum variant is represented by, keyed by the variant name.
+fn variant(variant_name: &str, value: Value) -> Value {
+    let mut entries = Map::new();
+    entries.insert(variant_name.to_owned(), value);
+    Value::Object(entries)
+}
+
+/// The state of an array value being serialized.
+#[doc(hidden)]
+pub struct SerializeArray {
+    variant_name: Option<&'static str>,
+    elements: Vec<Value>,
+}
+
+impl SerializeArray {
+    fn push<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.elements.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        let array = Value::Array(self.elements);
+        match self.variant_name {
+            Some(variant_name) => Ok(variant(variant_name, array)),
+            None => Ok(array),
+        }
+    }
+}
+
+impl serde::ser::SerializeSeq for SerializeArray {
+    type Ok = Value;
+    type Error = UbjError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        SerializeArray::end(self)
+    }
+}
+
+impl serde::ser::SerializeTuple for SerializeArray {
+    type Ok = Value;
+    type Error = UbjError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        SerializeArray::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Value;
+    type Error = UbjError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        SerializeArray::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Value;
+    type Error = UbjError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        SerializeArray::end(self)
+    }
+}
+
+/// The state of an object value being serialized.
+#[doc(hidden)]
+pub struct SerializeObject {
+    variant_name: Option<&'static str>,
+    entries: Map,
+    next_key: Option<String>,
+}
+
+impl SerializeObject {
+    fn end(self) -> UbjResult<Value> {
+        let object = Value::Object(self.entries);
+        match self.variant_name {
+            Some(variant_name) => Ok(variant(variant_name, object)),
+            None => Ok(object),
+        }
+    }
+}
+
+impl serde::ser::SerializeMap for SerializeObject {
+    type Ok = Value;
+    type Error = UbjError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        // Keys of objects can only be strings in Universal Binary JSON
+        match key.serialize(ValueSerializer)? {
+            Value::String(key) => {
+                self.next_key = Some(key);
+                Ok(())
+            }
+            other => Err(illegal_key_type(&other)),
+        }
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        let key = self.next_key.take().unwrap_or_default();
+        self.entries.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        SerializeObject::end(self)
+    }
+}
+
+impl serde::ser::SerializeStruct for SerializeObject {
+    type Ok = Value;
+    type Error = UbjError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.entries
+            .insert(key.to_owned(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        SerializeObject::end(self)
+    }
+}
+
+impl serde::ser::SerializeStructVariant for SerializeObject {
+    type Ok = Value;
+    type Error = UbjError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> UbjResult<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        self.entries
+            .insert(key.to_owned(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> UbjResult<Value> {
+        SerializeObject::end(self)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Converts a Rust value of type `T` to a [`Value`] tree, without going through bytes.
+///
+/// # Examples
+/// ```rust
+/// use serde_ubj::Value;
+///
+/// let value = serde_ubj::to_value(&(7_i8, 300_u16, "a")).unwrap();
+/// assert_eq!(
+///     value,
+///     Value::Array(vec![Value::Int8(7), Value::Int16(300), Value::String("a".into())])
+/// );
+/// ```
+pub fn to_value<T>(value: &T) -> UbjResult<Value>
+where
+    T: ?Sized + serde::Serialize,
+{
+    value.serialize(ValueSerializer)
+}
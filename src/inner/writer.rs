@@ -1,12 +1,18 @@
 use crate::inner::IoWrite;
 use crate::inner::err::{UbjError, UbjResult};
 use crate::inner::markers::UbjMarker;
+use alloc::vec::Vec;
 
 pub struct UbjWriter<W>
 where
     W: IoWrite,
 {
     underlying: W,
+    // Whether lengths (of strings) and counts (of containers) get written with the `l` marker,
+    // rather than the narrowest marker they fit
+    fixed_width_lengths: bool,
+    // The bytes written since the capture started, which have not reached the underlying writer
+    capture: Option<Vec<u8>>,
 }
 
 impl<W> UbjWriter<W>
@@ -15,7 +21,23 @@ where
 {
     /// Creates a new UBJ writer instance that delegates all operations to the provided writer.
     pub fn new(writer: W) -> Self {
-        Self { underlying: writer }
+        Self {
+            underlying: writer,
+            fixed_width_lengths: false,
+            capture: None,
+        }
+    }
+
+    /// Enables (or disables) writing all lengths (of strings) and counts (of containers) as int32
+    /// values with the `l` marker, even if they would fit a narrower one.
+    pub fn fixed_width_lengths(mut self, enabled: bool) -> Self {
+        self.fixed_width_lengths = enabled;
+        self
+    }
+
+    /// Unwraps the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.underlying
     }
 
     /// Flushes the underlying writer.
@@ -23,6 +45,24 @@ where
         self.underlying.flush().map_err(UbjError::from_io_error)
     }
 
+    /// Starts capturing all bytes written from now on in memory, rather than writing them to the
+    /// underlying writer. Nothing changes if the capture has started already.
+    pub fn start_capture(&mut self) {
+        if self.capture.is_none() {
+            self.capture = Some(Vec::new());
+        }
+    }
+
+    /// Returns the bytes captured so far (if any).
+    pub fn captured(&self) -> &[u8] {
+        self.capture.as_deref().unwrap_or_default()
+    }
+
+    /// Stops the capture and returns the bytes captured, which are up to the caller to write.
+    pub fn take_capture(&mut self) -> Vec<u8> {
+        self.capture.take().unwrap_or_default()
+    }
+
     // ---------------------------------------------------------------------------------
     //  S C A L A R   values
     // ---------------------------------------------------------------------------------
@@ -71,6 +111,21 @@ where
         }
     }
 
+    /// Writes an int16 value with the `I` marker, even if it would fit a narrower one.
+    pub fn write_fixed_int16(&mut self, v: i16) -> UbjResult<()> {
+        self.write_marker_and_payload(UbjMarker::Int16, &v.to_be_bytes())
+    }
+
+    /// Writes an int32 value with the `l` marker, even if it would fit a narrower one.
+    pub fn write_fixed_int32(&mut self, v: i32) -> UbjResult<()> {
+        self.write_marker_and_payload(UbjMarker::Int32, &v.to_be_bytes())
+    }
+
+    /// Writes an int64 value with the `L` marker, even if it would fit a narrower one.
+    pub fn write_fixed_int64(&mut self, v: i64) -> UbjResult<()> {
+        self.write_marker_and_payload(UbjMarker::Int64, &v.to_be_bytes())
+    }
+
     pub fn write_float32(&mut self, v: f32) -> UbjResult<()> {
         self.write_marker_and_payload(UbjMarker::Float32, &v.to_be_bytes())
     }
@@ -105,15 +160,11 @@ where
 
     pub fn write_unmarked_string(&mut self, v: &str) -> UbjResult<()> {
         let payload = v.as_bytes();
-        let len = payload.len();
-        if len <= (i64::MAX as usize) {
-            self.write_int64(len as i64)
-                .and_then(|_| self.write_payload(payload))
-        } else {
-            Err(UbjError::Unsupported(
-                "Rust String values with length greater than i64::MAX",
-            ))
-        }
+        self.write_length(
+            payload.len(),
+            "Rust String values with length greater than i64::MAX",
+        )
+        .and_then(|_| self.write_payload(payload))
     }
 
     pub fn write_marked_string(&mut self, v: &str) -> UbjResult<()> {
@@ -121,6 +172,20 @@ where
             .and_then(|_| self.write_unmarked_string(v))
     }
 
+    /// Writes a high-precision number, that is the `H` marker followed by the given decimal text
+    /// exactly as a string would be written.
+    pub fn write_high_precision(&mut self, v: &str) -> UbjResult<()> {
+        self.write_marker(UbjMarker::HighPrecision)
+            .and_then(|_| self.write_unmarked_string(v))
+    }
+
+    /// Writes the given bytes as a strongly typed array of uint8 values, that is the `[$U#` header
+    /// followed by the raw bytes in one single write.
+    pub fn write_bytes(&mut self, v: &[u8]) -> UbjResult<()> {
+        self.write_start_optimized_array(Some(UbjMarker::Uint8), v.len())
+            .and_then(|_| self.write_payload(v))
+    }
+
     // ---------------------------------------------------------------------------------
     //  C O M P O U N D   values
     // ---------------------------------------------------------------------------------
@@ -141,20 +206,70 @@ where
         self.write_marker(UbjMarker::EndObject)
     }
 
+    /// Writes the header of an optimized array, that is the `[` marker followed by the optional
+    /// `$` type of its elements and the mandatory `#` count of its elements.
+    ///
+    /// Optimized arrays have no closing `]` marker. When a type is declared, the caller has to
+    /// write exactly `count` elements by means of the `write_unmarked_*` methods, or otherwise
+    /// by stripping the marker of each element.
+    pub fn write_start_optimized_array(
+        &mut self,
+        element_type: Option<UbjMarker>,
+        count: usize,
+    ) -> UbjResult<()> {
+        self.write_marker(UbjMarker::StartArray)?;
+        self.write_optimized_header(element_type, count)
+    }
+
+    /// Writes the given bytes as they are, assuming they already are valid Universal Binary JSON.
+    pub fn write_raw(&mut self, bytes: &[u8]) -> UbjResult<()> {
+        self.write_payload(bytes)
+    }
+
+    /// Writes the optional `$` type and the mandatory `#` count of an optimized container, whose
+    /// opening marker has been written already.
+    pub fn write_optimized_header(&mut self, t: Option<UbjMarker>, count: usize) -> UbjResult<()> {
+        if let Some(marker) = t {
+            self.write_marker(UbjMarker::ContainerType)?;
+            self.write_marker(marker)?;
+        }
+        self.write_marker(UbjMarker::ContainerCount)?;
+        self.write_length(count, "Rust containers with length greater than i64::MAX")
+    }
 
     // PRIVATE methods
     // --------------------------
 
-    fn write_marker(&mut self, marker: UbjMarker) -> UbjResult<()> {
-        self.underlying
-            .write_all(&[marker as u8])
-            .map_err(UbjError::from_io_error)
+    fn write_length(&mut self, len: usize, unsupported: &'static str) -> UbjResult<()> {
+        if self.fixed_width_lengths {
+            match i32::try_from(len) {
+                Ok(len) => self.write_fixed_int32(len),
+                Err(_) => Err(UbjError::Unsupported(
+                    "fixed-width lengths greater than i32::MAX",
+                )),
+            }
+        } else if len <= (i64::MAX as usize) {
+            self.write_int64(len as i64)
+        } else {
+            Err(UbjError::Unsupported(unsupported))
+        }
+    }
+
+    pub fn write_marker(&mut self, marker: UbjMarker) -> UbjResult<()> {
+        self.write_payload(&[marker as u8])
     }
 
     fn write_payload(&mut self, payload: &[u8]) -> UbjResult<()> {
-        self.underlying
-            .write_all(payload)
-            .map_err(UbjError::from_io_error)
+        match &mut self.capture {
+            Some(capture) => {
+                capture.extend_from_slice(payload);
+                Ok(())
+            }
+            None => self
+                .underlying
+                .write_all(payload)
+                .map_err(UbjError::from_io_error),
+        }
     }
 
     fn write_marker_and_payload(&mut self, marker: UbjMarker, payload: &[u8]) -> UbjResult<()> {
@@ -24,15 +24,47 @@ mod inner {
 
     pub mod de;
     pub mod err;
+    pub mod event;
+    #[cfg(feature = "std")]
+    pub mod in_place;
+    mod macros;
     mod markers;
-    mod reader;
+    pub mod number;
+    mod patch;
+    mod pointer;
+    pub mod raw;
+    pub mod reader;
     pub mod ser;
+    pub mod value;
+    pub mod value_de;
+    pub mod value_ser;
     mod writer;
 }
 
 // Re-exports
 
-pub use inner::de::{from_vec, from_buf_reader};
+pub use inner::de::UbjDeserializer as Deserializer;
+pub use inner::de::{
+    ArrayIter, StreamDeserializer, UbjDeserializer, from_buf_reader, from_slice, from_vec,
+};
+pub use inner::reader::{IoRead, SliceRead, UbjRead};
 pub use inner::err::UbjError;
+pub use inner::event::{ContainerHeader, Event, EventReader, EventWriter, Text};
 pub use inner::err::UbjResult;
-pub use inner::ser::{to_vec, to_writer};
+#[cfg(feature = "std")]
+pub use inner::in_place::{patch_in_place, patch_in_place_with_options};
+pub use inner::number::Number;
+pub use inner::raw::{BorrowedRawValue, RawValue};
+pub use inner::value::{Map, Value};
+pub use inner::value_de::from_value;
+pub use inner::value_ser::{ValueSerializer, to_value};
+pub use inner::ser::{
+    SeqWriter, UbjSerializerOptions, to_vec, to_vec_with_options, to_writer, to_writer_with_options,
+};
+
+// Not public API, but used by the ubj! macro
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use alloc::vec;
+}
@@ -25,10 +25,15 @@ macro_rules! assert_deserialize_value_ok {
         assert_deserialize_value_ok!($bytes, $t, $expected, 8192)
     };
     ($bytes:expr, $t:ty, $expected:expr, $capacity:expr) => {
-        let mut reader = buf_reader_of($bytes, $capacity);
+        let bytes: &[u8] = $bytes;
+        let mut reader = buf_reader_of(bytes, $capacity);
+        let expected: $t = $expected;
         let result: UbjResult<$t> = from_buf_reader(&mut reader);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), $expected);
+        assert_eq!(result.unwrap(), expected);
+        let result: UbjResult<$t> = from_slice(bytes);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), expected);
     };
 }
 
@@ -43,12 +48,27 @@ macro_rules! assert_deserialize_split_utf8_ok {
 }
 
 
+// The Other error carries its message only when built with std
+#[cfg(feature = "std")]
+macro_rules! other_err {
+    () => { UbjError::Other(_) };
+}
+
+#[cfg(not(feature = "std"))]
+macro_rules! other_err {
+    () => { UbjError::Other };
+}
+
 macro_rules! assert_deserialize_value_err {
     ($bytes:expr, $t:ty, $expected:pat) => {
-        let mut buf_reader = buf_reader_of($bytes, 8192);
+        let bytes: &[u8] = $bytes;
+        let mut buf_reader = buf_reader_of(bytes, 8192);
         let result: UbjResult<$t> = from_buf_reader(&mut buf_reader);
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), $expected));
+        let result: UbjResult<$t> = from_slice(bytes);
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), $expected));
     };
 }
 
@@ -78,6 +98,10 @@ fn deserialize_to_i8() {
     assert_deserialize_value_ok! (&[0x69, 0x7B], i8, 123_i8);
     assert_deserialize_value_err!(&[0x69,     ], i8, UbjError::UnexpectedEof);
     assert_deserialize_value_err!(&[0xFF, 0x85], i8, UbjError::UnexpectedMarker(0xFF));
+    assert_deserialize_value_ok! (&[0x55, 0x7F], i8, 127_i8);
+    assert_deserialize_value_ok! (&[0x49, 0xFF, 0x80], i8, -128_i8);
+    assert_deserialize_value_err!(&[0x55, 0x80], i8, UbjError::OutOfRange("i8"));
+    assert_deserialize_value_err!(&[0x49, 0x01, 0x00], i8, UbjError::OutOfRange("i8"));
 }
 
 #[test]
@@ -86,6 +110,10 @@ fn deserialize_to_u8() {
     assert_deserialize_value_ok! (&[0x55, 0xFE], u8, 254_u8);
     assert_deserialize_value_err!(&[0x55,     ], u8, UbjError::UnexpectedEof);
     assert_deserialize_value_err!(&[0xFF, 0x7B], u8, UbjError::UnexpectedMarker(0xFF));
+    assert_deserialize_value_ok! (&[0x69, 0x7B], u8, 123_u8);
+    assert_deserialize_value_ok! (&[0x6C, 0x00, 0x00, 0x00, 0xFF], u8, 255_u8);
+    assert_deserialize_value_err!(&[0x69, 0xFF], u8, UbjError::OutOfRange("u8"));
+    assert_deserialize_value_err!(&[0x49, 0x01, 0x00], u8, UbjError::OutOfRange("u8"));
 }
 
 #[test]
@@ -94,6 +122,9 @@ fn deserialize_to_i16() {
     assert_deserialize_value_ok! (&[0x49, 0x7F, 0xBC], i16, 32700_i16);
     assert_deserialize_value_err!(&[0x49, 0x7F,     ], i16, UbjError::UnexpectedEof);
     assert_deserialize_value_err!(&[0xFF, 0x80, 0x44], i16, UbjError::UnexpectedMarker(0xFF));
+    assert_deserialize_value_ok! (&[0x55, 0xFE], i16, 254_i16);
+    assert_deserialize_value_ok! (&[0x69, 0x85], i16, -123_i16);
+    assert_deserialize_value_err!(&[0x6C, 0x00, 0x00, 0xFD, 0xE8], i16, UbjError::OutOfRange("i16"));
 }
 
 #[test]
@@ -104,6 +135,10 @@ fn deserialize_to_i32() {
     assert_deserialize_value_ok! (&[0x6C, 0x4A, 0x5B, 0x17, 0x00], i32, 1247483648_i32);
     assert_deserialize_value_err!(&[0x6C, 0x4A, 0x5B,           ], i32, UbjError::UnexpectedEof);
     assert_deserialize_value_err!(&[0xFF, 0x4A, 0x5B, 0x17, 0x00], i32, UbjError::UnexpectedMarker(0xFF));
+    assert_deserialize_value_ok! (&[0x55, 0x05], i32, 5_i32);
+    assert_deserialize_value_ok! (&[0x49, 0x80, 0x44], i32, -32700_i32);
+    assert_deserialize_value_ok! (&[0x4C, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 0x00, 0x00], i32, i32::MIN);
+    assert_deserialize_value_err!(&[0x4C, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00], i32, UbjError::OutOfRange("i32"));
 }
 
 #[test]
@@ -128,6 +163,25 @@ fn deserialize_to_i64() {
     assert_deserialize_value_ok! (&[0x4C, 0x00, 0x03, 0x46, 0xDC, 0x5D, 0x63, 0x88, 0x65], i64, 922337203685477_i64);
     assert_deserialize_value_err!(&[0x4C, 0x00, 0x03, 0x46, 0xDC,                       ], i64, UbjError::UnexpectedEof);
     assert_deserialize_value_err!(&[0xFF, 0x00, 0x03, 0x46, 0xDC, 0x5D, 0x63, 0x88, 0x65], i64, UbjError::UnexpectedMarker(0xFF));
+    assert_deserialize_value_ok! (&[0x69, 0x85], i64, -123_i64);
+    assert_deserialize_value_ok! (&[0x6C, 0x4A, 0x5B, 0x17, 0x00], i64, 1247483648_i64);
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x13][..], b"9223372036854775808"].concat(), i64, UbjError::OutOfRange("i64"));
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x14][..], b"99999999999999999999"].concat(), i64, UbjError::OutOfRange("i64"));
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x03][..], b"1e3"].concat(), i64, UbjError::InvalidHighPrecision("i64"));
+}
+
+#[test]
+fn deserialize_integers_round_trip() {
+    let value = (5_i32, -300_i64, 200_i16, model::MyTupleStruct(1, 2, 3));
+    let bytes = to_vec(&value).unwrap();
+    assert_deserialize_value_ok!(&bytes, (i32, i64, i16, model::MyTupleStruct), value);
+}
+
+#[test]
+fn deserialize_unsigned_integers_round_trip() {
+    let value = (80_u16, 4000000000_u32, 42_u64, u64::MAX);
+    let bytes = to_vec(&value).unwrap();
+    assert_deserialize_value_ok!(&bytes, (u16, u32, u64, u64), value);
 }
 
 #[test]
@@ -142,23 +196,43 @@ fn deserialize_6_2_split_bytes_to_i64() {
 }
 #[test]
 fn deserialize_to_i128() {
-    assert_deserialize_value_err! (&[], i128, UbjError::Unsupported("i128"));
+    assert_deserialize_value_ok! (&[0x4C, 0xFF, 0xFC, 0xB9, 0x23, 0xA2, 0x9C, 0x77, 0x9B], i128, -922337203685477_i128);
+    assert_deserialize_value_ok! (&[&[0x48, 0x55, 0x14][..], b"-9223372036854775809"].concat(), i128, i64::MIN as i128 - 1);
+    assert_deserialize_value_ok! (&[&[0x48, 0x55, 0x28][..], b"-170141183460469231731687303715884105728"].concat(), i128, i128::MIN);
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x27][..], b"170141183460469231731687303715884105728"].concat(), i128, UbjError::OutOfRange("i128"));
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x03][..], b"1.5"].concat(), i128, UbjError::InvalidHighPrecision("i128"));
+    assert_deserialize_value_err!(&[], i128, UbjError::UnexpectedEof);
 }
 #[test]
 fn deserialize_to_u16() {
-    assert_deserialize_value_err! (&[], u16, UbjError::Unsupported("u16"));
+    assert_deserialize_value_ok! (&[0x55, 0xFE], u16, 254_u16);
+    assert_deserialize_value_ok! (&[0x6C, 0x00, 0x00, 0xFF, 0xFF], u16, 65535_u16);
+    assert_deserialize_value_err!(&[0x6C, 0x00, 0x01, 0x00, 0x00], u16, UbjError::OutOfRange("u16"));
+    assert_deserialize_value_err!(&[0x69, 0x85], u16, UbjError::OutOfRange("u16"));
+    assert_deserialize_value_err!(&[0xFF, 0x7B], u16, UbjError::UnexpectedMarker(0xFF));
 }
 #[test]
 fn deserialize_to_u32() {
-    assert_deserialize_value_err! (&[], u32, UbjError::Unsupported("u32"));
+    assert_deserialize_value_ok! (&[0x49, 0x7F, 0xBC], u32, 32700_u32);
+    assert_deserialize_value_ok! (&[0x4C, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFA], u32, 4294967290_u32);
+    assert_deserialize_value_err!(&[0x4C, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00], u32, UbjError::OutOfRange("u32"));
+    assert_deserialize_value_err!(&[0x6C, 0xFF, 0xFF, 0x02, 0x18], u32, UbjError::OutOfRange("u32"));
+    assert_deserialize_value_err!(&[0x6C, 0x00, 0x00, 0xFD], u32, UbjError::UnexpectedEof);
 }
 #[test]
 fn deserialize_to_u64() {
-    assert_deserialize_value_err! (&[], u64, UbjError::Unsupported("u64"));
+    assert_deserialize_value_ok! (&[0x55, 0x7B], u64, 123_u64);
+    assert_deserialize_value_ok! (&[0x4C, 0x00, 0x03, 0x46, 0xDC, 0x5D, 0x63, 0x88, 0x65], u64, 922337203685477_u64);
+    assert_deserialize_value_err!(&[0x4C, 0xFF, 0xFC, 0xB9, 0x23, 0xA2, 0x9C, 0x77, 0x9B], u64, UbjError::OutOfRange("u64"));
+    assert_deserialize_value_ok! (&[&[0x48, 0x55, 0x14][..], b"18446744073709551615"].concat(), u64, u64::MAX);
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x14][..], b"18446744073709551616"].concat(), u64, UbjError::OutOfRange("u64"));
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x02][..], b"-1"].concat(), u64, UbjError::OutOfRange("u64"));
 }
 #[test]
 fn deserialize_to_u128() {
-    assert_deserialize_value_err! (&[], u128, UbjError::Unsupported("u128"));
+    assert_deserialize_value_ok! (&[0x49, 0x01, 0x2C], u128, 300_u128);
+    assert_deserialize_value_ok! (&[&[0x48, 0x55, 0x27][..], b"340282366920938463463374607431768211455"].concat(), u128, u128::MAX);
+    assert_deserialize_value_err!(&[0x69, 0xFF], u128, UbjError::OutOfRange("u128"));
 }
 
 #[test]
@@ -169,6 +243,7 @@ fn deserialize_to_f32() {
 }
 
 #[test]
+#[allow(clippy::excessive_precision)]
 fn deserialize_3_1_split_bytes_to_f32() {
     let ubj_bytes: &[u8] = &[
         // chunk #1 of 4 bytes
@@ -186,6 +261,26 @@ fn deserialize_to_f64() {
     assert_deserialize_value_err!(&[0x53, 0x41, 0x70, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00], f64, UbjError::UnexpectedMarker(0x53));
 }
 
+#[test]
+fn deserialize_high_precision_to_float() {
+    assert_deserialize_value_ok! (&[&[0x48, 0x55, 0x06][..], b"1.25e2"].concat(), f64, 125_f64);
+    assert_deserialize_value_ok! (&[&[0x48, 0x55, 0x05][..], b"-0.15"].concat(), f32, -0.15_f32);
+    assert_deserialize_value_err!(&[&[0x48, 0x55, 0x03][..], b"one"].concat(), f64, UbjError::InvalidHighPrecision("f64"));
+}
+
+#[test]
+fn deserialize_high_precision_err_not_a_json_number() {
+    for text in ["inf", "-inf", "NaN", "infinity", "+1", "01", "1.", ".5", "1e", "1e+", "-", "", "0x10", "1_000"] {
+        let bytes = [&[0x48, 0x55, text.len() as u8][..], text.as_bytes()].concat();
+        assert_deserialize_value_err!(&bytes, f64, UbjError::InvalidHighPrecision("f64"));
+        assert_deserialize_value_err!(&bytes, i64, UbjError::InvalidHighPrecision("i64"));
+    }
+    for text in ["0", "-0", "10", "0.5", "-1.25E+2", "1e-3"] {
+        let bytes = [&[0x48, 0x55, text.len() as u8][..], text.as_bytes()].concat();
+        assert_deserialize_value_ok!(&bytes, f64, text.parse::<f64>().unwrap());
+    }
+}
+
 #[test]
 fn deserialize_5_3_split_bytes_to_f64() {
     let ubj_bytes: &[u8] = &[
@@ -370,6 +465,86 @@ fn deserialize_2147483648_contiguous_bytes_to_utf8_string() {
     assert_deserialize_value_ok!(ubj.as_slice(), String, text);
 }
 
+#[test]
+fn deserialize_high_precision_to_string() {
+    //                                      [H]   [U]   [22]
+    assert_deserialize_value_ok!(&[&[0x48, 0x55, 0x16][..], b"1234567890123456789012"].concat(), String, String::from("1234567890123456789012"));
+}
+
+
+#[test]
+fn deserialize_bytes() {
+    let my_data: [u8; 3] = [123_u8, 45_u8, 67_u8];
+    assert_deserialize_value_ok!(&[
+        0x7B,
+        //   [u]  [5]   [b]   [y]   [t]   [e]   [s]
+            0x55, 0x05, 0x62, 0x79, 0x74, 0x65, 0x73,
+        //   [[]  [$]   [U]   [#]   [U]   [3]
+            0x5B, 0x24, 0x55, 0x23, 0x55, 0x03,   0x7B, 0x2D, 0x43,
+        //   [u]  [8]   [b]   [y]   [t]   [e]   [_]   [b]   [u]   [f]
+            0x55, 0x08, 0x62, 0x79, 0x74, 0x65, 0x5F, 0x62, 0x75, 0x66,
+        //   [[]  [$]   [U]   [#]   [U]   [3]
+            0x5B, 0x24, 0x55, 0x23, 0x55, 0x03,   0x7B, 0x2D, 0x43,
+        0x7D
+    ], model::MyBytesWrapper, model::MyBytesWrapper {
+        bytes: serde_bytes::ByteBuf::from(&my_data[..]),
+        byte_buf: my_data.to_vec(),
+    });
+}
+
+#[test]
+fn deserialize_bytes_split_across_chunks() {
+    let mut ubj_bytes = vec![0x5B, 0x24, 0x55, 0x23, 0x49, 0x03, 0xE8];
+    let my_data: Vec<u8> = (0..1000).map(|n| n as u8).collect();
+    ubj_bytes.extend_from_slice(&my_data);
+    assert_deserialize_value_ok!(&ubj_bytes, serde_bytes::ByteBuf, serde_bytes::ByteBuf::from(my_data), 64);
+}
+
+#[test]
+fn deserialize_bytes_from_plain_array() {
+    assert_deserialize_value_ok!(&[
+        0x5B,
+            0x55, 0x7B,
+            0x69, 0x2D,
+            0x55, 0xC8,
+        0x5D
+    ], serde_bytes::ByteBuf, serde_bytes::ByteBuf::from(vec![123_u8, 45_u8, 200_u8]));
+}
+
+#[test]
+fn deserialize_bytes_from_counted_array() {
+    assert_deserialize_value_ok!(&[
+    //   [[]  [#]   [U]   [2]
+        0x5B, 0x23, 0x55, 0x02,
+            0x55, 0x7B,
+            0x69, 0x2D,
+    ], serde_bytes::ByteBuf, serde_bytes::ByteBuf::from(vec![123_u8, 45_u8]));
+}
+
+#[test]
+fn deserialize_bytes_err_negative_int8() {
+    assert_deserialize_value_err!(&[0x5B, 0x69, 0xFF, 0x5D], serde_bytes::ByteBuf, UbjError::OutOfRange("u8"));
+}
+
+#[test]
+fn deserialize_bytes_err_wrong_type() {
+    //                                     [[]  [$]   [I]   [#]   [U]   [1]
+    assert_deserialize_value_err!(&[0x5B, 0x24, 0x49, 0x23, 0x55, 0x01, 0x00, 0x7B], serde_bytes::ByteBuf, UbjError::UnexpectedMarker(0x49));
+}
+
+#[test]
+fn deserialize_bytes_err_huge_count() {
+    //                                     [[]  [#]   [L]   [68719476736]                                   [i]   [1]
+    assert_deserialize_value_err!(&[0x5B, 0x23, 0x4C, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x69, 0x01], serde_bytes::ByteBuf, UbjError::UnexpectedEof);
+    //                                     [[]  [$]   [U]   [#]   [L]   [68719476736]                                   [1]
+    assert_deserialize_value_err!(&[0x5B, 0x24, 0x55, 0x23, 0x4C, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x01], serde_bytes::ByteBuf, UbjError::UnexpectedEof);
+}
+
+#[test]
+fn deserialize_string_err_huge_length() {
+    //                                     [S]  [L]   [68719476736]                                   [a]
+    assert_deserialize_value_err!(&[0x53, 0x4C, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x61], String, UbjError::UnexpectedEof);
+}
 
 // ---------------------------------------------------------------------------------
 // C O M P O U N D   values
@@ -473,6 +648,43 @@ fn deserialize_to_struct() {
     ], MyFieldsStruct, MyFieldsStruct { x: 123, y: false, z: String::from("value") });
 }
 
+#[test]
+fn deserialize_to_struct_ignoring_unknown_fields() {
+    use model::MyFieldsStruct;
+    assert_deserialize_value_ok!(&[
+        0x7B,
+        //   [u]  [1]   [a]    a string
+            0x55, 0x01, 0x61,    0x53, 0x55, 0x03, 0x61, 0x62, 0x63,
+            0x55, 0x01, 0x78,    0x69, 0x7B,
+        //   [u]  [1]   [b]    a typed array of int32
+            0x55, 0x01, 0x62,    0x5B, 0x24, 0x6C, 0x23, 0x55, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02,
+            0x55, 0x01, 0x79,    0x46,
+        //   [u]  [1]   [c]    an object nesting a counted array, a typed object and a high-precision number
+            0x55, 0x01, 0x63,    0x7B,
+                                      0x55, 0x01, 0x64,    0x5B, 0x23, 0x55, 0x02, 0x5A, 0x44, 0x41, 0x70, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+                                      0x55, 0x01, 0x65,    0x7B, 0x24, 0x53, 0x23, 0x55, 0x01, 0x55, 0x01, 0x66, 0x55, 0x02, 0x67, 0x68,
+                                      0x55, 0x01, 0x68,    0x48, 0x55, 0x02, 0x31, 0x32,
+                                 0x7D,
+            0x55, 0x01, 0x7A,    0x53, 0x55, 0x05, 0x76, 0x61, 0x6C, 0x75, 0x65,
+        //   [u]  [1]   [d]    a plain array of arrays
+            0x55, 0x01, 0x64,    0x5B, 0x5B, 0x54, 0x5D, 0x5B, 0x5D, 0x43, 0x41, 0x5D,
+        0x7D,
+    ], MyFieldsStruct, MyFieldsStruct { x: 123, y: false, z: String::from("value") });
+}
+
+#[test]
+fn deserialize_to_struct_ignoring_unknown_fields_err() {
+    use model::MyFieldsStruct;
+    assert_deserialize_value_err!(&[
+        0x7B,
+            0x55, 0x01, 0x61,    0x5B, 0x24, 0x6C, 0x23, 0x55, 0x02, 0x00, 0x00, 0x00, 0x01,
+    ], MyFieldsStruct, UbjError::UnexpectedEof);
+    assert_deserialize_value_err!(&[
+        0x7B,
+            0x55, 0x01, 0x61,    0xFF,
+    ], MyFieldsStruct, UbjError::UnexpectedMarker(0xFF));
+}
+
 
 
 // ENUMS
@@ -549,6 +761,70 @@ fn deserialize_to_struct_variant() {
 
 
 
+// SELF-DESCRIBING
+// ---------------
+
+#[test]
+fn deserialize_any_to_untagged_variants() {
+    use model::MyUntaggedEnum;
+    assert_deserialize_value_ok!(&[0x5A], MyUntaggedEnum, MyUntaggedEnum::MyNullVariant(()));
+    assert_deserialize_value_ok!(&[0x55, 0xFE], MyUntaggedEnum, MyUntaggedEnum::MyIntVariant(254));
+    assert_deserialize_value_ok!(&[0x69, 0x85], MyUntaggedEnum, MyUntaggedEnum::MyIntVariant(-123));
+    assert_deserialize_value_ok!(&[0x49, 0x80, 0x44], MyUntaggedEnum, MyUntaggedEnum::MyIntVariant(-32700));
+    assert_deserialize_value_ok!(&[0x6C, 0x4A, 0x5B, 0x17, 0x00], MyUntaggedEnum, MyUntaggedEnum::MyIntVariant(1247483648));
+    assert_deserialize_value_ok!(&[0x4C, 0x00, 0x03, 0x46, 0xDC, 0x5D, 0x63, 0x88, 0x65], MyUntaggedEnum, MyUntaggedEnum::MyIntVariant(922337203685477));
+    assert_deserialize_value_ok!(&[0x64, 0x3E, 0x20, 0x00, 0x00], MyUntaggedEnum, MyUntaggedEnum::MyFloatVariant(0.15625));
+    assert_deserialize_value_ok!(&[0x44, 0x41, 0x70, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00], MyUntaggedEnum, MyUntaggedEnum::MyFloatVariant(16777216.125));
+    assert_deserialize_value_ok!(&[0x53, 0x55, 0x02, 0x61, 0x62], MyUntaggedEnum, MyUntaggedEnum::MyTextVariant(String::from("ab")));
+    assert_deserialize_value_ok!(&[&[0x48, 0x55, 0x02][..], b"12"].concat(), MyUntaggedEnum, MyUntaggedEnum::MyIntVariant(12));
+    assert_deserialize_value_ok!(&[&[0x48, 0x55, 0x04][..], b"1.25"].concat(), MyUntaggedEnum, MyUntaggedEnum::MyTextVariant(String::from("1.25")));
+    assert_deserialize_value_err!(&[0xFF], MyUntaggedEnum, UbjError::UnexpectedMarker(0xFF));
+}
+
+#[test]
+fn deserialize_any_to_untagged_containers() {
+    use model::MyUntaggedEnum;
+    assert_deserialize_value_ok!(&[
+        0x5B,
+        //   [[]  [$]   [i]   [#]   [U]   [2]
+            0x5B, 0x24, 0x69, 0x23, 0x55, 0x02,   0x0C, 0x40,
+            0x7B,
+                0x55, 0x01, 0x78,    0x69, 0x7B,
+                0x55, 0x01, 0x79,    0x46,
+            0x7D,
+        0x5D
+    ], MyUntaggedEnum, MyUntaggedEnum::MyListVariant(vec![
+        MyUntaggedEnum::MyListVariant(vec![MyUntaggedEnum::MyIntVariant(12), MyUntaggedEnum::MyIntVariant(64)]),
+        MyUntaggedEnum::MyFieldsVariant { x: 123, y: false },
+    ]));
+}
+
+#[test]
+fn deserialize_any_after_no_ops() {
+    use model::MyUntaggedEnum;
+    assert_deserialize_value_ok!(&[0x4E, 0x4E, 0x55, 0xFE], MyUntaggedEnum, MyUntaggedEnum::MyIntVariant(254));
+    assert_deserialize_value_ok!(&[
+        0x4E, 0x5B,
+            0x4E, 0x69, 0x0C,
+        //   [[]  [$]   [U]   [#]   [U]   [2]
+            0x5B, 0x24, 0x55, 0x23, 0x55, 0x02,   0x4E, 0x4E,
+        0x5D
+    ], MyUntaggedEnum, MyUntaggedEnum::MyListVariant(vec![
+        MyUntaggedEnum::MyIntVariant(12),
+        MyUntaggedEnum::MyListVariant(vec![MyUntaggedEnum::MyIntVariant(78), MyUntaggedEnum::MyIntVariant(78)]),
+    ]));
+    assert_deserialize_value_err!(&[0x4E], MyUntaggedEnum, UbjError::UnexpectedEof);
+}
+
+#[test]
+fn deserialize_any_err_not_a_json_number() {
+    use model::MyUntaggedEnum;
+    for text in ["+5", "007", "NaN", "1_000"] {
+        let bytes = [&[0x48, 0x55, text.len() as u8][..], text.as_bytes()].concat();
+        assert_deserialize_value_err!(&bytes, MyUntaggedEnum, UbjError::InvalidHighPrecision("any"));
+    }
+}
+
 // MAPS-LIKE
 // ---------
 use core::hash::BuildHasherDefault;
@@ -580,6 +856,415 @@ fn deserialize_to_map_key_str() {
 
 
 
+// ---------------------------------------------------------------------------------
+//  O P T I M I Z E D   containers
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn deserialize_optimized_to_vector_of_same_type() {
+    let ubj_bytes = [
+    //   [[]  [$]   [i]   [#]   [U]   [3]
+        0x5B, 0x24, 0x69, 0x23, 0x55, 0x03,
+            0x0C,
+            0x40,
+            0x7B,
+    ];
+    assert_deserialize_value_ok!(&ubj_bytes, Vec<i8>, vec![12_i8, 64_i8, 123_i8]);
+}
+
+#[test]
+fn deserialize_optimized_to_vector_of_strings() {
+    let ubj_bytes = [
+    //   [[]  [$]   [S]   [#]   [U]   [2]
+        0x5B, 0x24, 0x53, 0x23, 0x55, 0x02,
+            0x55, 0x02, 0x61, 0x62,
+            0x55, 0x01, 0x63,
+    ];
+    assert_deserialize_value_ok!(&ubj_bytes, Vec<String>, vec![String::from("ab"), String::from("c")]);
+}
+
+#[test]
+fn deserialize_optimized_to_vector_of_counted_elements() {
+    let ubj_bytes = [
+    //   [[]  [#]   [U]   [2]
+        0x5B, 0x23, 0x55, 0x02,
+            0x69, 0x0C,
+            0x69, 0x40,
+    ];
+    assert_deserialize_value_ok!(&ubj_bytes, Vec<i8>, vec![12_i8, 64_i8]);
+}
+
+#[test]
+fn deserialize_optimized_to_empty_vector() {
+    //                                   [[]  [#]   [U]   [0]
+    assert_deserialize_value_ok!(&[0x5B, 0x23, 0x55, 0x00], Vec<i8>, vec![]);
+}
+
+#[test]
+fn deserialize_optimized_to_nested_vectors() {
+    let ubj_bytes = [
+    //   [[]  [#]   [U]   [2]
+        0x5B, 0x23, 0x55, 0x02,
+        //   [[]  [$]   [C]   [#]   [U]   [2]
+            0x5B, 0x24, 0x43, 0x23, 0x55, 0x02, 0x61, 0x62,
+        //   [[]  [$]   [C]   [#]   [U]   [1]
+            0x5B, 0x24, 0x43, 0x23, 0x55, 0x01, 0x63,
+    ];
+    assert_deserialize_value_ok!(&ubj_bytes, Vec<Vec<char>>, vec![vec!['a', 'b'], vec!['c']]);
+}
+
+#[test]
+fn deserialize_optimized_to_tuple() {
+    let ubj_bytes = [
+    //   [[]  [$]   [I]   [#]   [U]   [3]
+        0x5B, 0x24, 0x49, 0x23, 0x55, 0x03,
+            0x00, 0x0C,
+            0x80, 0x44,
+            0x7F, 0xBC,
+    ];
+    assert_deserialize_value_ok!(&ubj_bytes, (i16, i16, i16), (12_i16, -32700_i16, 32700_i16));
+}
+
+#[test]
+fn deserialize_optimized_to_tuple_err_too_many_elements() {
+    let ubj_bytes = [
+    //   [[]  [$]   [i]   [#]   [U]   [3]
+        0x5B, 0x24, 0x69, 0x23, 0x55, 0x03,
+            0x0C,
+            0x40,
+            0x7B,
+    ];
+    assert_deserialize_value_err!(&ubj_bytes, (i8, i8), other_err!());
+}
+
+#[test]
+fn deserialize_optimized_to_struct() {
+    use model::MyFieldsStruct;
+    assert_deserialize_value_ok!(&[
+    //   [{]  [#]   [U]   [3]
+        0x7B, 0x23, 0x55, 0x03,
+            0x55, 0x01, 0x78,    0x69, 0x7B,
+            0x55, 0x01, 0x79,    0x46,
+            0x55, 0x01, 0x7A,    0x53, 0x55, 0x05, 0x76, 0x61, 0x6C, 0x75, 0x65,
+    ], MyFieldsStruct, MyFieldsStruct {
+        x: 123_i8,
+        y: false,
+        z: String::from("value"),
+    });
+}
+
+#[test]
+fn deserialize_optimized_to_map_of_same_type() {
+    let my_map_value = indexmap_with_default!{
+        FnvHasher;
+        String::from("key1") => 123_i8,
+        String::from("key2") => 45_i8,
+    };
+    assert_deserialize_value_ok!(&[
+    //   [{]  [$]   [i]   [#]   [U]   [2]
+        0x7B, 0x24, 0x69, 0x23, 0x55, 0x02,
+    //        [u]  [4]   [k]   [e]   [y]   [1]
+            0x55, 0x04, 0x6B, 0x65, 0x79, 0x31,   0x7B,
+    //        [u]  [4]   [k]   [e]   [y]   [2]
+            0x55, 0x04, 0x6B, 0x65, 0x79, 0x32,   0x2D,
+    ], MyMapType, my_map_value);
+}
+
+#[test]
+fn deserialize_optimized_to_tuple_variant() {
+    use model::MyEnum;
+    assert_deserialize_value_ok!(&[
+    //   [{]  [#]   [U]   [1]
+        0x7B, 0x23, 0x55, 0x01,
+        //   [u]  [14]
+            0x55, 0x0E, 0x4D, 0x79, 0x54, 0x75, 0x70, 0x6C, 0x65, 0x56, 0x61, 0x72, 0x69, 0x61, 0x6E, 0x74,
+        //   [[]  [#]   [U]   [3]
+            0x5B, 0x23, 0x55, 0x03,
+                0x69, 0x7B,
+                0x49, 0x7F, 0xBC,
+                0x6C, 0x4A, 0x5B, 0x17, 0x00,
+    ], MyEnum, MyEnum::MyTupleVariant(123_i8, 32700_i16, 1247483648_i32));
+}
+
+#[test]
+fn deserialize_optimized_round_trip() {
+    use model::MyFieldsStruct;
+    let options = UbjSerializerOptions::new().optimize_containers(true);
+    let value = vec![
+        MyFieldsStruct { x: 1_i8, y: true, z: String::from("one") },
+        MyFieldsStruct { x: -2_i8, y: false, z: String::from("two") },
+    ];
+    let bytes = to_vec_with_options(&value, options).unwrap();
+    assert_deserialize_value_ok!(&bytes, Vec<MyFieldsStruct>, value);
+}
+
+#[test]
+fn deserialize_optimized_err_negative_count() {
+    //                                    [[]  [#]   [i]   [-1]
+    assert_deserialize_value_err!(&[0x5B, 0x23, 0x69, 0xFF], Vec<i8>, UbjError::NegativeLength(-1));
+}
+
+#[test]
+fn deserialize_optimized_err_type_without_count() {
+    //                                    [[]  [$]   [i]   [i]
+    assert_deserialize_value_err!(&[0x5B, 0x24, 0x69, 0x69, 0x0C], Vec<i8>, UbjError::UnexpectedMarker(0x69));
+}
+
+// ---------------------------------------------------------------------------------
+//  B O R R O W E D   values
+// ---------------------------------------------------------------------------------
+
+#[derive(Debug, serde::Deserialize, PartialEq)]
+struct MyBorrowingStruct<'a> {
+    text: &'a str,
+    #[serde(borrow)]
+    cow: std::borrow::Cow<'a, str>,
+    #[serde(with = "serde_bytes")]
+    bytes: &'a [u8],
+}
+
+const MY_BORROWING_STRUCT: [u8; 37] = [
+    0x7B,
+    //   [u]  [4]   [t]   [e]   [x]   [t]    [S]   [u]  [2]   [h]   [i]
+        0x55, 0x04, 0x74, 0x65, 0x78, 0x74,   0x53, 0x55, 0x02, 0x68, 0x69,
+    //   [u]  [3]   [c]   [o]   [w]          [S]   [u]  [1]   [!]
+        0x55, 0x03, 0x63, 0x6F, 0x77,         0x53, 0x55, 0x01, 0x21,
+    //   [u]  [5]   [b]   [y]   [t]   [e]   [s]    [[]  [$]   [U]   [#]   [U]   [2]
+        0x55, 0x05, 0x62, 0x79, 0x74, 0x65, 0x73,   0x5B, 0x24, 0x55, 0x23, 0x55, 0x02, 0x7B, 0x2D,
+    0x7D,
+];
+
+#[test]
+fn deserialize_borrowed_from_slice() {
+    let result: UbjResult<MyBorrowingStruct> = from_slice(&MY_BORROWING_STRUCT);
+    let value = result.unwrap();
+    assert_eq!(value, MyBorrowingStruct {
+        text: "hi",
+        cow: std::borrow::Cow::Borrowed("!"),
+        bytes: &[123_u8, 45_u8],
+    });
+    assert!(matches!(value.cow, std::borrow::Cow::Borrowed(_)));
+    // The borrowed values point straight into the slice
+    assert!(MY_BORROWING_STRUCT.as_ptr_range().contains(&value.text.as_ptr()));
+}
+
+#[test]
+fn deserialize_borrowed_from_buf_reader_err() {
+    let mut buf_reader = buf_reader_of(&MY_BORROWING_STRUCT, 8192);
+    let result: UbjResult<MyBorrowingStruct> = from_buf_reader(&mut buf_reader);
+    assert!(matches!(result.unwrap_err(), other_err!()));
+}
+
+#[test]
+fn deserialize_borrowed_err_invalid_utf8() {
+    let result: UbjResult<&str> = from_slice(&[0x53, 0x55, 0x02, 0xC3, 0x28]);
+    assert!(matches!(result.unwrap_err(), UbjError::Utf8Error(_)));
+}
+
+#[test]
+fn deserialize_borrowed_err_truncated() {
+    let result: UbjResult<&str> = from_slice(&[0x53, 0x55, 0x05, 0x68, 0x69]);
+    assert!(matches!(result.unwrap_err(), UbjError::UnexpectedEof));
+}
+
+// ---------------------------------------------------------------------------------
+//  T R A I L I N G   bytes
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn deserialize_err_trailing_bytes() {
+    let result: UbjResult<i8> = from_slice(&[0x69, 0x07, 0x69]);
+    assert!(matches!(result.unwrap_err(), UbjError::TrailingBytes(2)));
+    let result: UbjResult<i8> = from_vec(vec![0x69, 0x07, 0x4E, 0x5A]);
+    assert!(matches!(result.unwrap_err(), UbjError::TrailingBytes(3)));
+}
+
+#[test]
+fn deserialize_trailing_no_ops() {
+    let result: UbjResult<i8> = from_slice(&[0x69, 0x07, 0x4E, 0x4E]);
+    assert_eq!(result.unwrap(), 7_i8);
+    let result: UbjResult<i8> = from_vec(vec![0x69, 0x07, 0x4E]);
+    assert_eq!(result.unwrap(), 7_i8);
+}
+
+#[test]
+fn deserialize_no_ops_within_containers() {
+    //           [[]   [i]   [7]   [N]   []]
+    let bytes = [0x5B, 0x69, 0x07, 0x4E, 0x5D];
+    assert_eq!(from_slice::<Value>(&bytes).unwrap(), ubj!([int8(7)]));
+    assert_eq!(from_slice::<Vec<i8>>(&bytes).unwrap(), vec![7]);
+    assert_eq!(from_slice::<(i8,)>(&bytes).unwrap(), (7,));
+    assert!(from_slice::<serde::de::IgnoredAny>(&bytes).is_ok());
+    //           [{]   [N]   [U]   [1]   [a]   [N]   [i]   [7]   [N]   [}]
+    let bytes = [0x7B, 0x4E, 0x55, 0x01, 0x61, 0x4E, 0x69, 0x07, 0x4E, 0x7D];
+    assert_eq!(from_slice::<Value>(&bytes).unwrap(), ubj!({"a": int8(7)}));
+    assert_eq!(from_slice::<std::collections::BTreeMap<String, i8>>(&bytes).unwrap()["a"], 7);
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<i8>("/a").unwrap(), Some(7));
+    assert!(from_slice::<serde::de::IgnoredAny>(&bytes).is_ok());
+    //           [[]   [#]   [U]   [2]   [N]   [i]   [7]   [N]   [i]   [8]
+    let bytes = [0x5B, 0x23, 0x55, 0x02, 0x4E, 0x69, 0x07, 0x4E, 0x69, 0x08];
+    assert_eq!(from_slice::<Vec<i8>>(&bytes).unwrap(), vec![7, 8]);
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<i8>("/1").unwrap(), Some(8));
+    assert_eq!(Deserializer::from_slice(&bytes).into_array_iter::<i8>().collect::<UbjResult<Vec<_>>>().unwrap(), vec![7, 8]);
+    // The values of a container declaring their type carry no marker, so N is one of them
+    //           [[]   [$]   [U]   [#]   [U]   [2]   [N]   [N]
+    let bytes = [0x5B, 0x24, 0x55, 0x23, 0x55, 0x02, 0x4E, 0x4E];
+    assert_eq!(from_slice::<Vec<u8>>(&bytes).unwrap(), vec![0x4E, 0x4E]);
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<u8>("/1").unwrap(), Some(0x4E));
+}
+
+#[test]
+fn deserialize_prefix_from_slice() {
+    use serde::Deserialize;
+    let bytes = [0x69, 0x07, 0x53, 0x55, 0x02, 0x68, 0x69];
+    let mut deserializer = UbjDeserializer::from_slice(&bytes);
+    assert_eq!(i8::deserialize(&mut deserializer).unwrap(), 7_i8);
+    assert!(matches!(deserializer.end(), Err(UbjError::TrailingBytes(2))));
+    assert_eq!(deserializer.into_inner(), &bytes[2..]);
+}
+
+#[test]
+fn deserialize_prefix_from_buf_reader() {
+    use serde::Deserialize;
+    use std::io::Read;
+    let bytes = [0x69, 0x07, 0x53, 0x55, 0x02, 0x68, 0x69];
+    let mut deserializer = UbjDeserializer::from_buf_reader(buf_reader_of(&bytes, 4));
+    assert_eq!(i8::deserialize(&mut deserializer).unwrap(), 7_i8);
+    let mut remaining = Vec::new();
+    deserializer.into_inner().read_to_end(&mut remaining).unwrap();
+    assert_eq!(remaining, &bytes[2..]);
+}
+
+#[test]
+fn deserialize_end_of_buf_reader() {
+    use serde::Deserialize;
+    let bytes = [0x69, 0x07, 0x4E];
+    let mut deserializer = UbjDeserializer::from_buf_reader(buf_reader_of(&bytes, 2));
+    assert_eq!(i8::deserialize(&mut deserializer).unwrap(), 7_i8);
+    assert!(deserializer.end().is_ok());
+}
+
+// ---------------------------------------------------------------------------------
+//  S T R E A M S   of values
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn deserialize_stream_from_buf_reader() {
+    use model::MyFieldsStruct;
+    let first = MyFieldsStruct { x: 1, y: true, z: String::from("one") };
+    let second = MyFieldsStruct { x: 2, y: false, z: String::from("two") };
+    let mut bytes = Vec::new();
+    to_writer(&mut bytes, &first).unwrap();
+    let second_offset = bytes.len() + 2;
+    bytes.extend_from_slice(&[0x4E, 0x4E]);
+    to_writer(&mut bytes, &second).unwrap();
+    bytes.push(0x4E);
+
+    let mut stream = Deserializer::from_buf_reader(buf_reader_of(&bytes, 5)).into_iter::<MyFieldsStruct>();
+    assert_eq!(stream.next().unwrap().unwrap(), first);
+    assert_eq!(stream.value_offset(), 0);
+    assert_eq!(stream.next().unwrap().unwrap(), second);
+    assert_eq!(stream.value_offset(), second_offset);
+    assert!(stream.next().is_none());
+    assert_eq!(stream.byte_offset(), bytes.len());
+}
+
+#[test]
+fn deserialize_stream_from_slice() {
+    let bytes = [0x53, 0x55, 0x01, 0x61, 0x53, 0x55, 0x01, 0x62];
+    let values: Vec<&str> = Deserializer::from_slice(&bytes)
+        .into_iter::<&str>()
+        .collect::<UbjResult<_>>()
+        .unwrap();
+    assert_eq!(values, vec!["a", "b"]);
+}
+
+#[test]
+fn deserialize_empty_stream() {
+    let mut stream = Deserializer::from_slice(&[]).into_iter::<i8>();
+    assert!(stream.next().is_none());
+    let mut stream = Deserializer::from_slice(&[0x4E]).into_iter::<i8>();
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn deserialize_stream_err_truncated_value() {
+    //                                          [i]   [7]   [I]   [1]
+    let mut stream = Deserializer::from_slice(&[0x69, 0x07, 0x49, 0x01]).into_iter::<i16>();
+    assert_eq!(stream.next().unwrap().unwrap(), 7_i16);
+    assert!(matches!(stream.next(), Some(Err(UbjError::UnexpectedEof))));
+    assert_eq!(stream.value_offset(), 2);
+    assert!(stream.next().is_none());
+}
+
+
+// ---------------------------------------------------------------------------------
+//  A R R A Y S   iterated lazily
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn deserialize_array_iter_from_buf_reader() {
+    use model::MyFieldsStruct;
+    let elements = vec![
+        MyFieldsStruct { x: 1, y: true, z: String::from("one") },
+        MyFieldsStruct { x: 2, y: false, z: String::from("two") },
+    ];
+    let bytes = to_vec(&elements).unwrap();
+
+    let mut iter = Deserializer::from_buf_reader(buf_reader_of(&bytes, 5)).into_array_iter::<MyFieldsStruct>();
+    assert_eq!(iter.next().unwrap().unwrap(), elements[0]);
+    assert_eq!(iter.next().unwrap().unwrap(), elements[1]);
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+    iter.end().unwrap();
+}
+
+#[test]
+fn deserialize_array_iter_with_count() {
+    //           [[]   [#]   [U]   [2]   [S]   [U]   [1]   [a]   [S]   [U]   [1]   [b]   [i]   [9]
+    let bytes = [0x5B, 0x23, 0x55, 0x02, 0x53, 0x55, 0x01, 0x61, 0x53, 0x55, 0x01, 0x62, 0x69, 0x09];
+    let mut iter = Deserializer::from_slice(&bytes).into_array_iter::<&str>();
+    assert_eq!(iter.next().unwrap().unwrap(), "a");
+    assert_eq!(iter.size_hint(), (1, Some(1)));
+    assert_eq!(iter.next().unwrap().unwrap(), "b");
+    assert!(iter.next().is_none());
+    assert!(matches!(iter.end(), Err(UbjError::TrailingBytes(12))));
+}
+
+#[test]
+fn deserialize_array_iter_with_type_and_count() {
+    //           [[]   [$]   [I]   [#]   [i]   [3]   [1]         [2]         [3]
+    let bytes = [0x5B, 0x24, 0x49, 0x23, 0x69, 0x03, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03];
+    let values: Vec<i16> = Deserializer::from_slice(&bytes)
+        .into_array_iter::<i16>()
+        .collect::<UbjResult<_>>()
+        .unwrap();
+    assert_eq!(values, vec![1, 2, 3]);
+}
+
+#[test]
+fn deserialize_empty_array_iter() {
+    let mut iter = Deserializer::from_slice(&[0x5B, 0x5D]).into_array_iter::<i8>();
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn deserialize_array_iter_err_not_an_array() {
+    let mut iter = Deserializer::from_slice(&[0x69, 0x07]).into_array_iter::<i8>();
+    assert!(matches!(iter.next(), Some(Err(UbjError::UnexpectedMarker(0x69)))));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn deserialize_array_iter_err_missing_end() {
+    //                                        [[]   [i]   [7]
+    let mut iter = Deserializer::from_slice(&[0x5B, 0x69, 0x07]).into_array_iter::<i8>();
+    assert_eq!(iter.next().unwrap().unwrap(), 7);
+    assert!(matches!(iter.next(), Some(Err(UbjError::UnexpectedEof))));
+    assert!(iter.next().is_none());
+}
+
+
 //
 // TODO fn deserialize_a_large_collection_of_diverse_value_types()
 //      to make sure the buffer is completely consumed
@@ -590,3 +1275,282 @@ fn deserialize_to_map_key_str() {
 
 
 
+
+// ---------------------------------------------------------------------------------
+//  J S O N   P O I N T E R S   looked up while streaming
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn deserialize_pointer_from_buf_reader() {
+    let bytes = to_vec(&ubj!({"a": [{"b": "skipped"}, {"b": "found"}], "c": 1})).unwrap();
+    let mut deserializer = Deserializer::from_buf_reader(buf_reader_of(&bytes, 3));
+    assert_eq!(deserializer.pointer::<String>("/a/1/b").unwrap(), Some(String::from("found")));
+}
+
+#[test]
+fn deserialize_pointer_from_slice() {
+    let bytes = to_vec(&ubj!({"a": [{"b": "skipped"}, {"b": "found"}], "c": 1})).unwrap();
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<&str>("/a/1/b").unwrap(), Some("found"));
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<u8>("/c").unwrap(), Some(1));
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<Value>("/a/0").unwrap(), Some(ubj!({"b": "skipped"})));
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<Value>("").unwrap(), Some(from_slice(&bytes).unwrap()));
+}
+
+#[test]
+fn deserialize_pointer_missing() {
+    let bytes = to_vec(&ubj!({"a": [{"b": "skipped"}], "c": 1})).unwrap();
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<Value>("/z").unwrap(), None);
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<Value>("/a/1").unwrap(), None);
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<Value>("/a/x").unwrap(), None);
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<Value>("/c/0").unwrap(), None);
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<Value>("c").unwrap(), None);
+}
+
+#[test]
+fn deserialize_pointer_in_optimized_containers() {
+    //           [{]   [$]   [I]   [#]   [U]   [2]   [U]   [1]   [a]   [1]         [U]   [1]   [b]   [2]
+    let bytes = [0x7B, 0x24, 0x49, 0x23, 0x55, 0x02, 0x55, 0x01, 0x61, 0x00, 0x01, 0x55, 0x01, 0x62, 0x00, 0x02];
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<i16>("/b").unwrap(), Some(2));
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<i16>("/c").unwrap(), None);
+
+    //           [[]   [#]   [U]   [2]   [S]   [U]   [1]   [a]   [i]   [9]
+    let bytes = [0x5B, 0x23, 0x55, 0x02, 0x53, 0x55, 0x01, 0x61, 0x69, 0x09];
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<i8>("/1").unwrap(), Some(9));
+    assert_eq!(Deserializer::from_slice(&bytes).pointer::<i8>("/2").unwrap(), None);
+}
+
+#[test]
+fn deserialize_pointer_raw() {
+    let bytes = to_vec(&ubj!({"a": [1, [true, "x"]]})).unwrap();
+    assert_eq!(
+        Deserializer::from_buf_reader(buf_reader_of(&bytes, 2)).pointer_raw("/a/1").unwrap(),
+        //        [[]   [#]   [U]   [2]   [T]   [S]   [U]   [1]   [x]
+        Some(vec![0x5B, 0x23, 0x55, 0x02, 0x54, 0x53, 0x55, 0x01, 0x78])
+    );
+    assert_eq!(Deserializer::from_slice(&bytes).pointer_raw("/a/2").unwrap(), None);
+}
+
+#[test]
+fn deserialize_pointer_raw_with_implied_marker() {
+    //           [[]   [$]   [I]   [#]   [i]   [3]   [1]         [2]         [3]
+    let bytes = [0x5B, 0x24, 0x49, 0x23, 0x69, 0x03, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03];
+    assert_eq!(
+        Deserializer::from_slice(&bytes).pointer_raw("/2").unwrap(),
+        //        [I]   [3]
+        Some(vec![0x49, 0x00, 0x03])
+    );
+}
+
+// ---------------------------------------------------------------------------------
+//  M E R G E   P A T C H E S   while streaming
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn deserialize_merge_patch_to() {
+    let source = ubj!({"a": "b", "c": {"d": "e", "f": "g"}, "h": [1, 2], "x": int16(1)});
+    let patch = ubj!({"a": "z", "c": {"f": null}, "h": [3], "i": {"j": null, "k": 1}});
+    let bytes = to_vec(&source).unwrap();
+
+    let mut patched = Vec::new();
+    Deserializer::from_buf_reader(buf_reader_of(&bytes, 3)).merge_patch_to(&patch, &mut patched).unwrap();
+    let mut expected = source.clone();
+    expected.merge_patch(&patch);
+    assert_eq!(from_slice::<Value>(&patched).unwrap(), expected);
+}
+
+#[test]
+fn deserialize_merge_patch_to_optimized_object() {
+    //           [{]   [$]   [I]   [#]   [U]   [2]   [U]   [1]   [a]   [1]         [U]   [1]   [b]   [2]
+    let bytes = [0x7B, 0x24, 0x49, 0x23, 0x55, 0x02, 0x55, 0x01, 0x61, 0x00, 0x01, 0x55, 0x01, 0x62, 0x00, 0x02];
+    let mut patched = Vec::new();
+    Deserializer::from_slice(&bytes).merge_patch_to(&ubj!({"b": true}), &mut patched).unwrap();
+    assert_eq!(
+        patched,
+        //   [{]   [U]   [1]   [a]   [I]   [1]         [U]   [1]   [b]   [T]   [}]
+        vec![0x7B, 0x55, 0x01, 0x61, 0x49, 0x00, 0x01, 0x55, 0x01, 0x62, 0x54, 0x7D]
+    );
+}
+
+#[test]
+fn deserialize_merge_patch_to_many_keys() {
+    let source = Value::Object((0..100_000).map(|i| (i.to_string(), Value::Int32(i))).collect());
+    let patch = Value::Object((0..100_000).step_by(2).map(|i| (i.to_string(), Value::Null)).collect());
+    let bytes = to_vec(&source).unwrap();
+
+    let mut patched = Vec::new();
+    Deserializer::from_slice(&bytes).merge_patch_to(&patch, &mut patched).unwrap();
+    let patched: Value = from_slice(&patched).unwrap();
+    assert_eq!(patched.as_object().unwrap().len(), 50_000);
+    assert_eq!(patched.as_object().unwrap()["99999"], Value::Int32(99_999));
+}
+
+#[test]
+fn deserialize_merge_patch_to_non_object() {
+    let bytes = to_vec(&ubj!([1, 2])).unwrap();
+    let mut patched = Vec::new();
+    Deserializer::from_slice(&bytes).merge_patch_to(&ubj!({"a": {"b": null}}), &mut patched).unwrap();
+    assert_eq!(patched, to_vec(&ubj!({"a": {}})).unwrap());
+}
+
+#[test]
+fn deserialize_merge_diff() {
+    let source = ubj!({"a": "b", "c": {"d": "e", "f": "g"}, "h": [1, 2], "x": 1});
+    let target = ubj!({"a": "z", "c": {"d": "e"}, "h": [1, 2], "x": int16(1), "i": {"k": 1}});
+    let source_bytes = to_vec(&source).unwrap();
+    let target_bytes = to_vec(&target).unwrap();
+
+    let patch = Deserializer::from_buf_reader(buf_reader_of(&source_bytes, 3))
+        .merge_diff(&mut Deserializer::from_slice(&target_bytes))
+        .unwrap();
+    assert_eq!(patch, source.merge_diff(&target));
+}
+
+#[test]
+fn deserialize_merge_diff_with_reordered_keys() {
+    let source = ubj!({"a": 1, "b": 2, "c": 3, "d": 4});
+    let target = ubj!({"a": 1, "c": 3, "b": 5});
+    let patch = Deserializer::from_slice(&to_vec(&source).unwrap())
+        .merge_diff(&mut Deserializer::from_slice(&to_vec(&target).unwrap()))
+        .unwrap();
+    assert_eq!(patch, source.merge_diff(&target));
+    assert_eq!(patch, ubj!({"d": null, "b": 5}));
+}
+
+#[test]
+fn deserialize_merge_diff_of_differently_encoded_values() {
+    let options = UbjSerializerOptions::new().optimize_containers(true);
+    let value = ubj!({"a": [1, 2, 3], "b": {"c": "d"}});
+    let patch = Deserializer::from_slice(&to_vec(&value).unwrap())
+        .merge_diff(&mut Deserializer::from_slice(&to_vec_with_options(&value, options).unwrap()))
+        .unwrap();
+    assert_eq!(patch, ubj!({}));
+}
+
+// ---------------------------------------------------------------------------------
+//  R A W   values passed through untouched
+// ---------------------------------------------------------------------------------
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct Envelope {
+    id: u8,
+    payload: RawValue,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct BorrowedEnvelope<'a> {
+    id: u8,
+    #[serde(borrow)]
+    payload: BorrowedRawValue<'a>,
+}
+
+#[test]
+fn deserialize_raw_value_from_buf_reader() {
+    let options = UbjSerializerOptions::new().optimize_containers(true);
+    let payload = to_vec_with_options(&ubj!({"a": [1, 2, 3], "b": "c"}), options).unwrap();
+    let bytes = to_vec(&Envelope { id: 1, payload: RawValue::new(payload.clone()).unwrap() }).unwrap();
+
+    let envelope: Envelope = from_buf_reader(&mut buf_reader_of(&bytes, 3)).unwrap();
+    assert_eq!(envelope.payload.as_bytes(), payload.as_slice());
+    assert_eq!(to_vec(&envelope).unwrap(), bytes);
+}
+
+#[test]
+fn deserialize_borrowed_raw_value_from_slice() {
+    //           [{]   [U]   [2]   [i]   [d]   [U]   [7]   [U]   [7]   [p]   [a]   [y]   [l]   [o]   [a]   [d]   [[]   [$]   [I]   [#]   [U]   [1]   [1]         [}]
+    let bytes = [0x7B, 0x55, 0x02, 0x69, 0x64, 0x55, 0x07, 0x55, 0x07, 0x70, 0x61, 0x79, 0x6C, 0x6F, 0x61, 0x64, 0x5B, 0x24, 0x49, 0x23, 0x55, 0x01, 0x00, 0x01, 0x7D];
+    let envelope: BorrowedEnvelope = from_slice(&bytes).unwrap();
+    assert_eq!(envelope.id, 7);
+    assert_eq!(envelope.payload.as_bytes(), &bytes[16..24]);
+    assert!(core::ptr::eq(envelope.payload.as_bytes(), &bytes[16..24]));
+    assert_eq!(envelope.payload.decode::<Vec<i16>>().unwrap(), vec![1]);
+    assert_eq!(to_vec_with_options(&envelope, UbjSerializerOptions::plain()).unwrap(), bytes);
+}
+
+#[test]
+fn deserialize_raw_value_with_implied_marker() {
+    //           [[]   [$]   [I]   [#]   [U]   [2]   [1]         [2]
+    let bytes = [0x5B, 0x24, 0x49, 0x23, 0x55, 0x02, 0x00, 0x01, 0x00, 0x02];
+    let values: Vec<RawValue> = from_slice(&bytes).unwrap();
+    //                                   [I]   [2]
+    assert_eq!(values[1].as_bytes(), &[0x49, 0x00, 0x02]);
+    assert!(from_slice::<Vec<BorrowedRawValue>>(&bytes).is_err());
+}
+
+#[test]
+fn deserialize_raw_value_into_value() {
+    let raw = RawValue::encode(&ubj!({"a": int32(1)})).unwrap();
+    assert_eq!(to_value(&raw).unwrap(), ubj!({"a": int32(1)}));
+    assert_eq!(from_value::<RawValue>(ubj!({"a": int32(1)})).unwrap(), raw);
+    assert_eq!(raw.decode::<Value>().unwrap(), ubj!({"a": int32(1)}));
+}
+
+#[test]
+fn deserialize_raw_value_err_invalid_bytes() {
+    //                                      [[]   [T]
+    assert!(matches!(RawValue::new(vec![0x5B, 0x54]), Err(UbjError::UnexpectedEof)));
+    //                                             [T]   [T]
+    assert!(matches!(BorrowedRawValue::new(&[0x54, 0x54]), Err(UbjError::TrailingBytes(1))));
+}
+
+// ---------------------------------------------------------------------------------
+//  P A T C H I N G   values in place
+// ---------------------------------------------------------------------------------
+
+#[cfg(feature = "std")]
+#[test]
+fn patch_in_place_scalar() {
+    let bytes = to_vec(&ubj!({"name": "x", "stats": {"count": int32(7), "on": false}, "tail": [1, 2]})).unwrap();
+    let mut file = std::io::Cursor::new(bytes.clone());
+    assert!(patch_in_place(&mut file, "/stats/count", &Number::from(8_i32)).unwrap());
+    assert!(patch_in_place(&mut file, "/stats/on", &true).unwrap());
+
+    let patched = file.into_inner();
+    assert_eq!(patched.len(), bytes.len());
+    assert_eq!(from_slice::<Value>(&patched).unwrap(), ubj!({"name": "x", "stats": {"count": int32(8), "on": true}, "tail": [1, 2]}));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn patch_in_place_with_implied_marker() {
+    //                                       [[]   [$]   [I]   [#]   [U]   [2]   [1]         [2]
+    let mut file = std::io::Cursor::new(vec![0x5B, 0x24, 0x49, 0x23, 0x55, 0x02, 0x00, 0x01, 0x00, 0x02]);
+    assert!(patch_in_place(&mut file, "/1", &Number::from(300_i16)).unwrap());
+    //                               [[]   [$]   [I]   [#]   [U]   [2]   [1]         [300]
+    assert_eq!(file.get_ref(), &vec![0x5B, 0x24, 0x49, 0x23, 0x55, 0x02, 0x00, 0x01, 0x01, 0x2C]);
+    assert!(matches!(patch_in_place(&mut file, "/0", &Number::from(1_u8)), Err(UbjError::UnexpectedMarker(0x49))));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn patch_in_place_from_current_position() {
+    let mut bytes = to_vec(&ubj!({"a": 1})).unwrap();
+    let second_offset = bytes.len() as u64;
+    bytes.extend(to_vec(&ubj!({"a": 2})).unwrap());
+    let mut file = std::io::Cursor::new(bytes);
+    file.set_position(second_offset);
+    assert!(patch_in_place(&mut file, "/a", &3_u8).unwrap());
+    assert_eq!(file.position(), second_offset);
+
+    let values: Vec<Value> = Deserializer::from_slice(file.get_ref()).into_iter().collect::<UbjResult<_>>().unwrap();
+    assert_eq!(values, vec![ubj!({"a": 1}), ubj!({"a": 3})]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn patch_in_place_not_found() {
+    let bytes = to_vec(&ubj!({"a": [1, 2]})).unwrap();
+    let mut file = std::io::Cursor::new(bytes.clone());
+    assert!(!patch_in_place(&mut file, "/a/2", &3_u8).unwrap());
+    assert!(!patch_in_place(&mut file, "/b", &3_u8).unwrap());
+    assert_eq!(file.into_inner(), bytes);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn patch_in_place_err_length_mismatch() {
+    let bytes = to_vec(&ubj!({"count": 7, "name": "abc"})).unwrap();
+    let mut file = std::io::Cursor::new(bytes.clone());
+    assert!(matches!(patch_in_place(&mut file, "/count", &1000_i32), Err(UbjError::LengthMismatch(2, 3))));
+    assert!(matches!(patch_in_place(&mut file, "/name", &"abcd"), Err(UbjError::LengthMismatch(6, 7))));
+    assert_eq!(file.into_inner(), bytes);
+}
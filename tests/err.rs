@@ -17,6 +17,7 @@ fn display_de_custom_error() {
 
 #[cfg(feature = "std")]
 #[test]
+#[allow(clippy::io_other_error)]
 fn display_io_error() {
     let io_err = std::io::Error::new(std::io::ErrorKind::Other, "Disk failure");
     let err = serde_ubj::UbjError::IO(io_err);
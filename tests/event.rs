@@ -0,0 +1,188 @@
+use serde_ubj::*;
+
+#[cfg(feature = "std")]
+fn buf_reader_of(bytes: &[u8], buffer_capacity: usize) -> std::io::BufReader<std::io::Cursor<&[u8]>> {
+    std::io::BufReader::with_capacity(buffer_capacity, std::io::Cursor::new(bytes))
+}
+
+#[cfg(all(not(feature = "std"), feature = "embedded-io"))]
+fn buf_reader_of(bytes: &[u8], _capacity: usize) -> &[u8] {
+    bytes
+}
+
+// Reading the events of some bytes and writing them back must give the very same bytes
+macro_rules! assert_events_round_trip_ok {
+    ($bytes:expr, $expected:expr) => {
+        let bytes: &[u8] = $bytes;
+        let expected: Vec<Event> = $expected;
+        let events: Vec<Event> = EventReader::from_slice(bytes).collect::<UbjResult<_>>().unwrap();
+        assert_eq!(events, expected);
+
+        let buf_events: Vec<Event> = EventReader::from_buf_reader(buf_reader_of(bytes, 2)).collect::<UbjResult<_>>().unwrap();
+        assert_eq!(buf_events, expected);
+
+        let mut writer = EventWriter::new(Vec::new());
+        for event in &events {
+            writer.write(event).unwrap();
+        }
+        assert_eq!(writer.into_inner().as_slice(), bytes);
+    };
+}
+
+fn text(text: &str, len_marker: char) -> Text {
+    Text { text: String::from(text), len_marker }
+}
+
+// ---------------------------------------------------------------------------------
+//  S C A L A R   events
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn events_scalars() {
+    assert_events_round_trip_ok!(
+        //[Z]   [T]   [F]   [C]   [a]   [l]   [7]
+        &[0x5A, 0x54, 0x46, 0x43, 0x61, 0x6C, 0x00, 0x00, 0x00, 0x07],
+        vec![Event::Null, Event::Bool(true), Event::Bool(false), Event::Char('a'), Event::Number(Number::from(7_i32))]
+    );
+}
+
+#[test]
+fn events_keep_length_markers() {
+    assert_events_round_trip_ok!(
+        //[S]   [i]   [2]   [h]   [i]   [S]   [I]   [2]         [h]   [i]   [H]   [l]   [3]                     [1]   [.]   [5]
+        &[0x53, 0x69, 0x02, 0x68, 0x69, 0x53, 0x49, 0x00, 0x02, 0x68, 0x69, 0x48, 0x6C, 0x00, 0x00, 0x00, 0x03, 0x31, 0x2E, 0x35],
+        vec![Event::String(text("hi", 'i')), Event::String(text("hi", 'I')), Event::HighPrecision(text("1.5", 'l'))]
+    );
+}
+
+#[test]
+fn events_keep_no_ops() {
+    assert_events_round_trip_ok!(
+        //[N]   [U]   [1]   [N]   [N]   [[]   [N]   [T]   []]   [N]
+        &[0x4E, 0x55, 0x01, 0x4E, 0x4E, 0x5B, 0x4E, 0x54, 0x5D, 0x4E],
+        vec![
+            Event::NoOp, Event::Number(Number::from(1_u8)), Event::NoOp, Event::NoOp,
+            Event::StartArray(ContainerHeader::default()), Event::NoOp, Event::Bool(true), Event::EndArray,
+            Event::NoOp,
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------------
+//  C O N T A I N E R   events
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn events_plain_containers() {
+    assert_events_round_trip_ok!(
+        //[{]   [U]   [1]   [a]   [[]   [Z]   []]   [N]   [U]   [1]   [b]   [{]   [}]   [}]
+        &[0x7B, 0x55, 0x01, 0x61, 0x5B, 0x5A, 0x5D, 0x4E, 0x55, 0x01, 0x62, 0x7B, 0x7D, 0x7D],
+        vec![
+            Event::StartObject(ContainerHeader::default()),
+                Event::Key(text("a", 'U')), Event::StartArray(ContainerHeader::default()), Event::Null, Event::EndArray,
+                Event::NoOp,
+                Event::Key(text("b", 'U')), Event::StartObject(ContainerHeader::default()), Event::EndObject,
+            Event::EndObject,
+        ]
+    );
+}
+
+#[test]
+fn events_counted_containers() {
+    let header = ContainerHeader { element_type: None, count: Some((2, 'I')) };
+    assert_events_round_trip_ok!(
+        //[[]   [#]   [I]   [2]         [T]   [{]   [#]   [U]   [1]   [U]   [1]   [a]   [Z]
+        &[0x5B, 0x23, 0x49, 0x00, 0x02, 0x54, 0x7B, 0x23, 0x55, 0x01, 0x55, 0x01, 0x61, 0x5A],
+        vec![
+            Event::StartArray(header),
+                Event::Bool(true),
+                Event::StartObject(ContainerHeader { element_type: None, count: Some((1, 'U')) }),
+                    Event::Key(text("a", 'U')), Event::Null,
+                Event::EndObject,
+            Event::EndArray,
+        ]
+    );
+}
+
+#[test]
+fn events_typed_containers() {
+    assert_events_round_trip_ok!(
+        //[[]   [$]   [I]   [#]   [i]   [2]   [1]         [2]         [{]   [$]   [S]   [#]   [U]   [1]   [U]   [1]   [a]   [U]   [1]   [b]
+        &[0x5B, 0x24, 0x49, 0x23, 0x69, 0x02, 0x00, 0x01, 0x00, 0x02, 0x7B, 0x24, 0x53, 0x23, 0x55, 0x01, 0x55, 0x01, 0x61, 0x55, 0x01, 0x62],
+        vec![
+            Event::StartArray(ContainerHeader { element_type: Some('I'), count: Some((2, 'i')) }),
+                Event::Number(Number::from(1_i16)), Event::Number(Number::from(2_i16)),
+            Event::EndArray,
+            Event::StartObject(ContainerHeader { element_type: Some('S'), count: Some((1, 'U')) }),
+                Event::Key(text("a", 'U')), Event::String(text("b", 'U')),
+            Event::EndObject,
+        ]
+    );
+}
+
+#[test]
+fn events_of_serialized_values() {
+    let options = UbjSerializerOptions::new().optimize_containers(true);
+    let value = ubj!({"a": [1, 2, 300], "b": {"c": [1.5, 2.5, 3.5]}, "d": high_precision("1e400")});
+    for bytes in [to_vec(&value).unwrap(), to_vec_with_options(&value, options).unwrap()] {
+        let mut writer = EventWriter::new(Vec::new());
+        for event in EventReader::from_slice(&bytes) {
+            writer.write(&event.unwrap()).unwrap();
+        }
+        assert_eq!(writer.into_inner(), bytes);
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//  R E W R I T I N G
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn events_rewrite_one_field() {
+    let options = UbjSerializerOptions::new().optimize_containers(true);
+    let bytes = to_vec_with_options(&ubj!({"name": "old", "ids": [1, 2, 3]}), options).unwrap();
+
+    let mut writer = EventWriter::new(Vec::new());
+    for event in EventReader::from_slice(&bytes) {
+        match event.unwrap() {
+            Event::String(t) if t.text == "old" => writer.write(&Event::String(Text::new("newer"))).unwrap(),
+            event => writer.write(&event).unwrap(),
+        }
+    }
+    let rewritten = writer.into_inner();
+    assert_eq!(rewritten.len(), bytes.len() + 2);
+    assert_eq!(&rewritten[rewritten.len() - 12..], &bytes[bytes.len() - 12..]);
+    assert_eq!(from_slice::<Value>(&rewritten).unwrap(), ubj!({"name": "newer", "ids": [1, 2, 3]}));
+}
+
+#[test]
+fn events_err_marker_not_matching_container_type() {
+    let mut writer = EventWriter::new(Vec::new());
+    writer.write(&Event::StartArray(ContainerHeader { element_type: Some('I'), count: Some((1, 'U')) })).unwrap();
+    assert!(matches!(writer.write(&Event::Number(Number::from(1_u8))), Err(UbjError::UnexpectedMarker(0x49))));
+}
+
+#[test]
+fn events_err_length_not_fitting_its_marker() {
+    let mut writer = EventWriter::new(Vec::new());
+    let long = "x".repeat(200);
+    assert!(matches!(writer.write(&Event::String(text(&long, 'i'))), Err(UbjError::OutOfRange("i8"))));
+}
+
+#[test]
+fn events_err_unbalanced_end() {
+    let mut writer = EventWriter::new(Vec::new());
+    writer.write(&Event::StartArray(ContainerHeader::default())).unwrap();
+    assert!(matches!(writer.write(&Event::EndObject), Err(UbjError::UnexpectedMarker(0x7D))));
+}
+
+#[test]
+fn events_err_unexpected_eof() {
+    //           [[]   [T]
+    let bytes = [0x5B, 0x54];
+    let mut events = EventReader::from_slice(&bytes);
+    assert!(events.next().unwrap().is_ok());
+    assert!(events.next().unwrap().is_ok());
+    assert!(matches!(events.next(), Some(Err(UbjError::UnexpectedEof))));
+    assert!(events.next().is_none());
+}
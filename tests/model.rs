@@ -10,17 +10,13 @@
 use serde::{Deserialize, Serialize};
 
 
-// #[derive(Serialize)]
-// pub struct MyBytesWrapper<'a> {
-//     #[serde(with = "serde_bytes")]
-//     pub bytes: &'a [u8],
-//
-//     //#[serde(with = "serde_bytes")]
-//     //byte_buf: Vec<u8>,
-//
-//     //#[serde(with = "serde_bytes")]
-//     //byte_array: [u8; 3],
-// }
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MyBytesWrapper {
+    pub bytes: serde_bytes::ByteBuf,
+
+    #[serde(with = "serde_bytes")]
+    pub byte_buf: Vec<u8>,
+}
 
 // -------------------------------------------------------------------------------------------------
 // A struct with no fields
@@ -152,6 +148,7 @@ pub struct MyFieldsStruct {
 //
 // // -------------------------------------------------------------------------------------------------
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[allow(clippy::enum_variant_names)]
 // #[repr(u8)]
 // #[serde(tag = "type")]
 // #[serde(tag = "t", content = "c")]
@@ -319,4 +316,18 @@ pub enum MyEnum {
 //             MyEnumVisitor {}
 //         )
 //     }
-// }
\ No newline at end of file
+// }
+
+// -------------------------------------------------------------------------------------------------
+// An enum whose variants get told apart by their content only (which requires deserialize_any)
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[allow(clippy::enum_variant_names)]
+#[serde(untagged)]
+pub enum MyUntaggedEnum {
+    MyNullVariant(()),
+    MyIntVariant(i64),
+    MyFloatVariant(f64),
+    MyTextVariant(String),
+    MyListVariant(Vec<MyUntaggedEnum>),
+    MyFieldsVariant { x: i8, y: bool },
+}
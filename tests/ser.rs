@@ -10,6 +10,16 @@ mod text;
 
 
 macro_rules! assert_serialize_ok {
+    ($value:expr, $expected:expr) => {
+        let mut buffer: Vec<u8> = Vec::new();
+        let result = to_writer_with_options(&mut buffer, &$value, UbjSerializerOptions::plain());
+        assert!(result.is_ok());
+        assert_eq!(buffer.as_slice(), $expected);
+    };
+}
+
+// Containers of known length get optimized by default
+macro_rules! assert_serialize_optimized_ok {
     ($value:expr, $expected:expr) => {
         let mut buffer: Vec<u8> = Vec::new();
         let result = to_writer(&mut buffer, &$value);
@@ -18,6 +28,15 @@ macro_rules! assert_serialize_ok {
     };
 }
 
+macro_rules! assert_serialize_with_options_ok {
+    ($value:expr, $options:expr, $expected:expr) => {
+        let mut buffer: Vec<u8> = Vec::new();
+        let result = to_writer_with_options(&mut buffer, &$value, $options);
+        assert!(result.is_ok());
+        assert_eq!(buffer.as_slice(), $expected);
+    };
+}
+
 macro_rules! assert_serialize_err {
     ($value:expr, $err:expr) => {
         let mut buffer = Vec::new();
@@ -152,17 +171,24 @@ fn serialize_i64() {
 #[test]
 fn serialize_u64() {
     assert_serialize_ok!(922337203685477_u64, &[0x4C, 0x00, 0x03, 0x46, 0xDC, 0x5D, 0x63, 0x88, 0x65]);
-    assert_serialize_err!(u64::MAX, UbjError::Unsupported("Rust u64 values greater than i64::MAX"));
+    //                            [H]   [U]   [20]  [1]   [8]   [4]   [4]   [6]   [7]   [4]   [4]   [0]   [7]   [3]   [7]   [0]   [9]   [5]   [5]   [1]   [6]   [1]   [5]
+    assert_serialize_ok!(u64::MAX, &[0x48, 0x55, 0x14, 0x31, 0x38, 0x34, 0x34, 0x36, 0x37, 0x34, 0x34, 0x30, 0x37, 0x33, 0x37, 0x30, 0x39, 0x35, 0x35, 0x31, 0x36, 0x31, 0x35]);
+    assert_serialize_ok!(i64::MAX as u64, &[0x4C, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
 }
 
 #[test]
 fn serialize_i128() {
-    assert_serialize_err!(i128::MAX, UbjError::Unsupported("Rust i128 values"));
+    assert_serialize_ok!(-12_i128, &[0x69, 0xF4]);
+    //                                             [H]   [U]   [20]  [-]   [9]   [2]   [2]   [3]   [3]   [7]   [2]   [0]   [3]   [6]   [8]   [5]   [4]   [7]   [7]   [5]   [8]   [0]   [9]
+    assert_serialize_ok!(i64::MIN as i128 - 1, &[0x48, 0x55, 0x14, 0x2D, 0x39, 0x32, 0x32, 0x33, 0x33, 0x37, 0x32, 0x30, 0x33, 0x36, 0x38, 0x35, 0x34, 0x37, 0x37, 0x35, 0x38, 0x30, 0x39]);
 }
 
 #[test]
 fn serialize_u128() {
-    assert_serialize_err!(u128::MAX, UbjError::Unsupported("Rust u128 values"));
+    assert_serialize_ok!(300_u128, &[0x49, 0x01, 0x2C]);
+    let mut expected = vec![0x48, 0x55, 0x27];
+    expected.extend_from_slice(u128::MAX.to_string().as_bytes());
+    assert_serialize_ok!(u128::MAX, expected.as_slice());
 }
 
 #[test]
@@ -224,23 +250,34 @@ fn serialize_string_2147483648_bytes_long() {
     assert_serialize_ok!(text, ubj.as_slice());
 }
 
-// TODO serialize_bytes
-// #[test]
-// #[ignore]
-// fn serialize_bytes() {
-//     let my_data: [u8; 3] = [123_u8, 45_u8, 67_u8];
-//     let my_bytes_wrapper = model::MyBytesWrapper {
-//         bytes: &my_data[..],
-//         //byte_buf: my_data.to_vec(),
-//         //byte_array: my_data,
-//     };
-//     assert_serialize_ok!(my_bytes_wrapper, &[
-//         0x5B,
-//             0x7B, 0x2D, 0x43,
-//         0x5D
-//     ]);
-// }
+#[test]
+fn serialize_bytes() {
+    let my_data: [u8; 3] = [123_u8, 45_u8, 67_u8];
+    let my_bytes_wrapper = model::MyBytesWrapper {
+        bytes: serde_bytes::ByteBuf::from(&my_data[..]),
+        byte_buf: my_data.to_vec(),
+    };
+    assert_serialize_ok!(my_bytes_wrapper, &[
+        0x7B,
+        //   [u]  [5]   [b]   [y]   [t]   [e]   [s]
+            0x55, 0x05, 0x62, 0x79, 0x74, 0x65, 0x73,
+        //   [[]  [$]   [U]   [#]   [U]   [3]
+            0x5B, 0x24, 0x55, 0x23, 0x55, 0x03,   0x7B, 0x2D, 0x43,
+        //   [u]  [8]   [b]   [y]   [t]   [e]   [_]   [b]   [u]   [f]
+            0x55, 0x08, 0x62, 0x79, 0x74, 0x65, 0x5F, 0x62, 0x75, 0x66,
+        //   [[]  [$]   [U]   [#]   [U]   [3]
+            0x5B, 0x24, 0x55, 0x23, 0x55, 0x03,   0x7B, 0x2D, 0x43,
+        0x7D
+    ]);
+}
 
+#[test]
+fn serialize_empty_bytes() {
+    assert_serialize_ok!(serde_bytes::Bytes::new(&[]), &[
+    //   [[]  [$]   [U]   [#]   [U]   [0]
+        0x5B, 0x24, 0x55, 0x23, 0x55, 0x00
+    ]);
+}
 
 // ---------------------------------------------------------------------------------
 //  C O M P O U N D   values
@@ -449,6 +486,25 @@ fn serialize_struct_variant() {
     );
 }
 
+#[test]
+fn serialize_untagged_variants() {
+    use model::MyUntaggedEnum;
+    assert_serialize_ok!(
+        MyUntaggedEnum::MyListVariant(vec![
+            MyUntaggedEnum::MyIntVariant(5),
+            MyUntaggedEnum::MyTextVariant(String::from("a")),
+            MyUntaggedEnum::MyNullVariant(()),
+        ]),
+        &[
+            0x5B,
+                0x55, 0x05,
+                0x53, 0x55, 0x01, 0x61,
+                0x5A,
+            0x5D
+        ]
+    );
+}
+
 
 // MAPS-LIKE
 // ---------
@@ -616,3 +672,400 @@ fn serialize_map_key_err_struct_variant() {
     use model::MyEnum;
     assert_serialize_map_key_err!(MyEnum::MyStructVariant{ x: 1_i8, y: false, z: String::from("value") }, "enum");
 }
+
+
+// ---------------------------------------------------------------------------------
+//  O P T I M I Z E D   containers
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn serialize_optimized_vector_of_same_type() {
+    assert_serialize_optimized_ok!(
+        vec![12_i8, 64_i8, 123_i8],
+        &[
+        //   [[]  [$]   [i]   [#]   [U]   [3]
+            0x5B, 0x24, 0x69, 0x23, 0x55, 0x03,
+                0x0C,
+                0x40,
+                0x7B,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_vector_of_widened_integers() {
+    // 12 is written as uint8, while the others are written as int16
+    assert_serialize_optimized_ok!(
+        vec![12_i16, -32700_i16, 32700_i16, 300_i16],
+        &[
+        //   [[]  [$]   [I]   [#]   [U]   [4]
+            0x5B, 0x24, 0x49, 0x23, 0x55, 0x04,
+                0x00, 0x0C,
+                0x80, 0x44,
+                0x7F, 0xBC,
+                0x01, 0x2C,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_vector_of_few_elements() {
+    // Declaring the type would not make it any smaller
+    assert_serialize_optimized_ok!(
+        vec![12_i8, 64_i8],
+        &[
+        //   [[]  [#]   [U]   [2]
+            0x5B, 0x23, 0x55, 0x02,
+                0x69, 0x0C,
+                0x69, 0x40,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_empty_vector() {
+    assert_serialize_optimized_ok!(
+        Vec::<i8>::new(),
+        //   [[]  [#]   [U]   [0]
+        &[0x5B, 0x23, 0x55, 0x00]
+    );
+}
+
+#[test]
+fn serialize_optimized_tuple_of_mixed_types() {
+    assert_serialize_optimized_ok!(
+        (true, 64_i8, String::from("a")),
+        &[
+        //   [[]  [#]   [U]   [3]
+            0x5B, 0x23, 0x55, 0x03,
+                0x54,
+                0x69, 0x40,
+                0x53, 0x55, 0x01, 0x61,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_nested_vectors() {
+    assert_serialize_optimized_ok!(
+        vec![vec![1.5_f32, 2.5_f32, 3.5_f32], vec![]],
+        &[
+        //   [[]  [#]   [U]   [2]
+            0x5B, 0x23, 0x55, 0x02,
+            //   [[]  [$]   [d]   [#]   [U]   [3]
+                0x5B, 0x24, 0x64, 0x23, 0x55, 0x03,
+                    0x3F, 0xC0, 0x00, 0x00,
+                    0x40, 0x20, 0x00, 0x00,
+                    0x40, 0x60, 0x00, 0x00,
+            //   [[]  [#]   [U]   [0]
+                0x5B, 0x23, 0x55, 0x00,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_deeply_nested_vectors() {
+    assert_serialize_optimized_ok!(
+        vec![vec![vec![], vec![1_i16, -2_i16, 3_i16]], vec![vec![300_i16]]],
+        &[
+        //   [[]  [#]   [U]   [2]
+            0x5B, 0x23, 0x55, 0x02,
+            //   [[]  [#]   [U]   [2]
+                0x5B, 0x23, 0x55, 0x02,
+                //   [[]  [#]   [U]   [0]
+                    0x5B, 0x23, 0x55, 0x00,
+                //   [[]  [$]   [i]   [#]   [U]   [3]
+                    0x5B, 0x24, 0x69, 0x23, 0x55, 0x03,   0x01, 0xFE, 0x03,
+            //   [[]  [#]   [U]   [1]
+                0x5B, 0x23, 0x55, 0x01,
+                //   [[]  [#]   [U]   [1]
+                    0x5B, 0x23, 0x55, 0x01,   0x49, 0x01, 0x2C,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_map_of_vectors() {
+    assert_serialize_optimized_ok!(
+        indexmap_with_default!{
+            FnvHasher;
+            "a" => vec![],
+            "b" => vec![1_u8, 2_u8, 3_u8],
+        },
+        &[
+        //   [{]  [#]   [U]   [2]
+            0x7B, 0x23, 0x55, 0x02,
+            //                       [[]  [#]   [U]   [0]
+                0x55, 0x01, 0x61,    0x5B, 0x23, 0x55, 0x00,
+            //                       [[]  [$]   [U]   [#]   [U]   [3]
+                0x55, 0x01, 0x62,    0x5B, 0x24, 0x55, 0x23, 0x55, 0x03,   0x01, 0x02, 0x03,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_struct() {
+    let my_struct = model::MyFieldsStruct {
+        x: 123_i8,
+        y: false,
+        z: String::from("value"),
+    };
+    assert_serialize_optimized_ok!(
+        my_struct,
+        &[
+        //   [{]  [#]   [U]   [3]
+            0x7B, 0x23, 0x55, 0x03,
+                0x55, 0x01, 0x78,    0x69, 0x7B,
+                0x55, 0x01, 0x79,    0x46,
+                0x55, 0x01, 0x7A,    0x53, 0x55, 0x05, 0x76, 0x61, 0x6C, 0x75, 0x65,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_map_of_same_type() {
+    assert_serialize_optimized_ok!(
+        indexmap_with_default!{
+            FnvHasher;
+            "a" => 1.5_f64,
+            "b" => 2.5_f64,
+            "c" => 3.5_f64,
+        },
+        &[
+        //   [{]  [$]   [D]   [#]   [U]   [3]
+            0x7B, 0x24, 0x44, 0x23, 0x55, 0x03,
+                0x55, 0x01, 0x61,    0x3F, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x55, 0x01, 0x62,    0x40, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x55, 0x01, 0x63,    0x40, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_tuple_variant() {
+    use model::MyEnum;
+    assert_serialize_optimized_ok!(
+        MyEnum::MyTupleVariant(123_i8, 32700_i16, 1247483648_i32),
+        &[
+            0x7B,
+            //   variant identifier
+                0x55, 0x0E, 0x4D, 0x79, 0x54, 0x75, 0x70, 0x6C, 0x65, 0x56, 0x61, 0x72, 0x69, 0x61, 0x6E, 0x74,
+            //   variant data (optimized sequence)
+            //   [[]  [#]   [U]   [3]
+                0x5B, 0x23, 0x55, 0x03,
+                    0x69, 0x7B,
+                    0x49, 0x7F, 0xBC,
+                    0x6C, 0x4A, 0x5B, 0x17, 0x00,
+            0x7D,
+        ]
+    );
+}
+
+#[test]
+fn serialize_optimized_unknown_length_sequence() {
+    struct Unsized;
+    impl serde::Serialize for Unsized {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeSeq;
+            let mut seq = serializer.serialize_seq(None)?;
+            seq.serialize_element(&1_i8)?;
+            seq.end()
+        }
+    }
+    // Containers of unknown length are streamed as they are
+    assert_serialize_optimized_ok!(Unsized, &[0x5B, 0x69, 0x01, 0x5D]);
+}
+
+
+// ---------------------------------------------------------------------------------
+//  A R R A Y S   written incrementally
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn serialize_seq_writer() {
+    use model::MyFieldsStruct;
+    let elements = vec![
+        MyFieldsStruct { x: 1, y: true, z: String::from("one") },
+        MyFieldsStruct { x: 2, y: false, z: String::from("two") },
+    ];
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut seq = SeqWriter::new(&mut buffer).unwrap();
+    for element in &elements {
+        seq.push(element).unwrap();
+    }
+    seq.finish().unwrap();
+    // The array itself is always plain, as its length is not known upfront
+    let mut expected = vec![0x5B];
+    for element in &elements {
+        expected.extend(to_vec(element).unwrap());
+    }
+    expected.push(0x5D);
+    assert_eq!(buffer, expected);
+}
+
+#[test]
+fn serialize_empty_seq_writer() {
+    let mut buffer: Vec<u8> = Vec::new();
+    SeqWriter::new(&mut buffer).unwrap().finish().unwrap();
+    assert_eq!(buffer.as_slice(), &[0x5B, 0x5D]);
+}
+
+#[test]
+fn serialize_seq_writer_finish_on_drop() {
+    let mut buffer: Vec<u8> = Vec::new();
+    {
+        let mut seq = SeqWriter::new(&mut buffer).unwrap().finish_on_drop(true);
+        seq.push(&7_i8).unwrap();
+    }
+    assert_eq!(buffer.as_slice(), &[0x5B, 0x69, 0x07, 0x5D]);
+
+    let mut buffer: Vec<u8> = Vec::new();
+    {
+        let mut seq = SeqWriter::new(&mut buffer).unwrap();
+        seq.push(&7_i8).unwrap();
+    }
+    assert_eq!(buffer.as_slice(), &[0x5B, 0x69, 0x07]);
+}
+
+#[test]
+fn serialize_seq_writer_after_err() {
+    let options = UbjSerializerOptions::new().optimize_containers(true);
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut seq = SeqWriter::with_options(&mut buffer, options).unwrap();
+    let illegal = vec![indexmap_with_default!{ FnvHasher; true => 1_i8 }];
+    assert!(matches!(seq.push(&illegal), Err(UbjError::IllegalKeyType("bool"))));
+    seq.push(&vec![7_i8]).unwrap();
+    seq.finish().unwrap();
+    //                             [[]   [[]   [#]   [U]   [1]   [i]   [7]   []]
+    assert_eq!(buffer.as_slice(), &[0x5B, 0x5B, 0x23, 0x55, 0x01, 0x69, 0x07, 0x5D]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn serialize_seq_writer_append() {
+    use std::io::Cursor;
+    let mut cursor = Cursor::new(Vec::new());
+    let mut seq = SeqWriter::append(&mut cursor).unwrap();
+    seq.push(&7_i8).unwrap();
+    seq.finish().unwrap();
+
+    let mut seq = SeqWriter::append(&mut cursor).unwrap();
+    seq.push("a").unwrap();
+    seq.finish().unwrap();
+    //                                            [[]   [i]   [7]   [S]   [U]   [1]   [a]   []]
+    assert_eq!(cursor.into_inner().as_slice(), &[0x5B, 0x69, 0x07, 0x53, 0x55, 0x01, 0x61, 0x5D]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn serialize_seq_writer_append_err_not_finished() {
+    use std::io::Cursor;
+    let mut cursor = Cursor::new(vec![0x5B, 0x69, 0x07]);
+    assert!(matches!(SeqWriter::append(&mut cursor), Err(UbjError::UnexpectedMarker(0x07))));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn serialize_seq_writer_append_err_not_a_plain_array() {
+    use std::io::Cursor;
+    //                                 [{]   [U]   [1]   [a]   [[]   []]   [}]
+    let mut cursor = Cursor::new(vec![0x7B, 0x55, 0x01, 0x61, 0x5B, 0x5D, 0x7D]);
+    assert!(matches!(SeqWriter::append(&mut cursor), Err(UbjError::UnexpectedMarker(0x7B))));
+    //                                 [{]   [U]   [1]   [a]   [[]   []]
+    let mut cursor = Cursor::new(vec![0x7B, 0x55, 0x01, 0x61, 0x5B, 0x5D]);
+    assert!(matches!(SeqWriter::append(&mut cursor), Err(UbjError::UnexpectedMarker(0x7B))));
+    //                                 [[]   [#]   [U]   [1]   [[]   []]
+    let mut cursor = Cursor::new(vec![0x5B, 0x23, 0x55, 0x01, 0x5B, 0x5D]);
+    assert!(matches!(SeqWriter::append(&mut cursor), Err(UbjError::UnexpectedMarker(0x23))));
+    //                                 []]
+    let mut cursor = Cursor::new(vec![0x5D]);
+    assert!(matches!(SeqWriter::append(&mut cursor), Err(UbjError::UnexpectedMarker(0x5D))));
+    //                                 [[]
+    let mut cursor = Cursor::new(vec![0x5B]);
+    assert!(matches!(SeqWriter::append(&mut cursor), Err(UbjError::UnexpectedMarker(0x5B))));
+}
+
+// ---------------------------------------------------------------------------------
+//  F I X E D - W I D T H   integers and lengths
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn serialize_fixed_width_integers() {
+    let options = UbjSerializerOptions::new().fixed_width_integers(true);
+    assert_serialize_with_options_ok!(7_i8, options, &[0x69, 0x07]);
+    assert_serialize_with_options_ok!(7_u8, options, &[0x55, 0x07]);
+    //                                                  [I]   [7]
+    assert_serialize_with_options_ok!(7_i16, options, &[0x49, 0x00, 0x07]);
+    //                                                  [l]   [7]
+    assert_serialize_with_options_ok!(7_i32, options, &[0x6C, 0x00, 0x00, 0x00, 0x07]);
+    assert_serialize_with_options_ok!(7_u16, options, &[0x6C, 0x00, 0x00, 0x00, 0x07]);
+    //                                                  [L]   [7]
+    assert_serialize_with_options_ok!(7_i64, options, &[0x4C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07]);
+    assert_serialize_with_options_ok!(7_u32, options, &[0x4C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07]);
+    assert_serialize_with_options_ok!(7_u64, options, &[0x4C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07]);
+    assert_serialize_with_options_ok!(-7_i128, options, &[0x4C, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xF9]);
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Counters {
+    hits: i32,
+    bytes: u64,
+}
+
+#[test]
+fn serialize_fixed_width_integers_of_struct() {
+    let options = UbjSerializerOptions::new().fixed_width_integers(true);
+    let small = to_vec_with_options(&Counters { hits: 1, bytes: 0 }, options).unwrap();
+    let large = to_vec_with_options(&Counters { hits: i32::MAX, bytes: i64::MAX as u64 }, options).unwrap();
+    assert_eq!(small.len(), large.len());
+    assert_eq!(from_slice::<Counters>(&large).unwrap().hits, i32::MAX);
+}
+
+#[test]
+fn serialize_fixed_width_integers_not_widened() {
+    let options = UbjSerializerOptions::new().fixed_width_integers(true).optimize_containers(true);
+    assert_serialize_with_options_ok!(
+        vec![1_i32, 2_i32, 300_i32],
+        options,
+        &[
+        //   [[]  [$]   [l]   [#]   [U]   [3]
+            0x5B, 0x24, 0x6C, 0x23, 0x55, 0x03,
+                0x00, 0x00, 0x00, 0x01,
+                0x00, 0x00, 0x00, 0x02,
+                0x00, 0x00, 0x01, 0x2C,
+        ]
+    );
+    assert_serialize_with_options_ok!(
+        (1_i16, 2_i32, 3_i16),
+        options,
+        &[
+        //   [[]  [#]   [U]   [3]
+            0x5B, 0x23, 0x55, 0x03,
+            //   [I]   [1]
+                0x49, 0x00, 0x01,
+            //   [l]   [2]
+                0x6C, 0x00, 0x00, 0x00, 0x02,
+            //   [I]   [3]
+                0x49, 0x00, 0x03,
+        ]
+    );
+}
+
+#[test]
+fn serialize_fixed_width_lengths() {
+    let options = UbjSerializerOptions::plain().fixed_width_lengths(true);
+    //                                                [S]   [l]   [1]                     [a]
+    assert_serialize_with_options_ok!("a", options, &[0x53, 0x6C, 0x00, 0x00, 0x00, 0x01, 0x61]);
+    assert_serialize_with_options_ok!(
+        indexmap_with_default!{FnvHasher; "k" => 7_u8},
+        options,
+        //[{]   [l]   [1]                     [k]   [U]   [7]   [}]
+        &[0x7B, 0x6C, 0x00, 0x00, 0x00, 0x01, 0x6B, 0x55, 0x07, 0x7D]
+    );
+    assert_serialize_with_options_ok!(
+        vec![1_u8, 2_u8, 3_u8],
+        options.optimize_containers(true),
+        //[[]   [$]   [U]   [#]   [l]   [3]                     [1]   [2]   [3]
+        &[0x5B, 0x24, 0x55, 0x23, 0x6C, 0x00, 0x00, 0x00, 0x03, 0x01, 0x02, 0x03]
+    );
+}
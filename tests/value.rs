@@ -0,0 +1,579 @@
+use serde_ubj::*;
+
+#[path = "model.rs"]
+mod model;
+
+fn object(entries: Vec<(&str, Value)>) -> Value {
+    Value::Object(entries.into_iter().collect())
+}
+
+// The Other error carries its message only when built with std
+#[cfg(feature = "std")]
+macro_rules! other_err {
+    () => { UbjError::Other(_) };
+}
+
+#[cfg(not(feature = "std"))]
+macro_rules! other_err {
+    () => { UbjError::Other };
+}
+
+// The plain format, as the optimized one may widen integers of Rust types to a common type
+fn to_plain_vec<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    to_vec_with_options(value, UbjSerializerOptions::plain()).unwrap()
+}
+
+// Converting to (and from) a value must agree with serializing to (and deserializing from) bytes
+macro_rules! assert_to_from_value_ok {
+    ($t:ty, $typed:expr) => {
+        let typed: $t = $typed;
+        let value = to_value(&typed).unwrap();
+        assert_eq!(to_plain_vec(&value), to_plain_vec(&typed));
+        assert_eq!(value, from_slice::<Value>(&to_plain_vec(&typed)).unwrap());
+        assert_eq!(from_value::<$t>(value).unwrap(), typed);
+    };
+}
+
+macro_rules! assert_value_round_trip_ok {
+    ($bytes:expr, $expected:expr) => {
+        let bytes: &[u8] = $bytes;
+        let expected: Value = $expected;
+        let value: Value = from_slice(bytes).unwrap();
+        assert_eq!(value, expected);
+        assert_eq!(to_plain_vec(&value).as_slice(), bytes);
+    };
+}
+
+
+// ---------------------------------------------------------------------------------
+//  S C A L A R   values
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn value_null() {
+    assert_value_round_trip_ok!(&[0x5A], Value::Null);
+}
+
+#[test]
+fn value_bool() {
+    assert_value_round_trip_ok!(&[0x54], Value::Bool(true));
+    assert_value_round_trip_ok!(&[0x46], Value::Bool(false));
+}
+
+#[test]
+fn value_integers_keep_their_width() {
+    assert_value_round_trip_ok!(&[0x69, 0xF9], Value::Int8(-7));
+    assert_value_round_trip_ok!(&[0x55, 0xC8], Value::Uint8(200));
+    assert_value_round_trip_ok!(&[0x49, 0x7F, 0xBC], Value::Int16(32700));
+    assert_value_round_trip_ok!(&[0x6C, 0x4A, 0x5B, 0x17, 0x00], Value::Int32(1247483648));
+    assert_value_round_trip_ok!(
+        &[0x4C, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        Value::Int64(i64::MAX)
+    );
+    // Non-minimal widths get read (and written back) as they are
+    assert_value_round_trip_ok!(&[0x49, 0x00, 0x05], Value::Int16(5));
+    assert_value_round_trip_ok!(&[0x6C, 0x00, 0x00, 0x00, 0x05], Value::Int32(5));
+    assert_value_round_trip_ok!(&[0x4C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05], Value::Int64(5));
+}
+
+#[test]
+fn value_floats() {
+    assert_value_round_trip_ok!(&[0x64, 0x3F, 0xC0, 0x00, 0x00], Value::Float32(1.5));
+    assert_value_round_trip_ok!(
+        &[0x44, 0x40, 0x09, 0x21, 0xFB, 0x54, 0x44, 0x2D, 0x18],
+        Value::Float64(std::f64::consts::PI)
+    );
+}
+
+#[test]
+fn value_char_and_string() {
+    assert_value_round_trip_ok!(&[0x43, 0x61], Value::Char('a'));
+    assert_value_round_trip_ok!(&[0x53, 0x55, 0x02, 0x68, 0x69], Value::String(String::from("hi")));
+}
+
+#[test]
+fn value_high_precision() {
+    //                            [H]   [U]   [4]   [1]   [.]   [2]   [5]
+    assert_value_round_trip_ok!(&[0x48, 0x55, 0x04, 0x31, 0x2E, 0x32, 0x35], Value::HighPrecision(String::from("1.25")));
+    //                            [H]   [U]   [2]   [4]   [2]
+    assert_value_round_trip_ok!(&[0x48, 0x55, 0x02, 0x34, 0x32], Value::HighPrecision(String::from("42")));
+}
+
+#[test]
+fn value_high_precision_err_not_a_json_number() {
+    for text in ["+5", "007", "NaN"] {
+        let result = from_value::<model::MyUntaggedEnum>(ubj!(high_precision(text)));
+        assert!(matches!(result, Err(UbjError::InvalidHighPrecision("any"))));
+    }
+    //           [H]   [U]   [3]   [a]   [b]   [c]
+    let bytes = [0x48, 0x55, 0x03, 0x61, 0x62, 0x63];
+    assert!(matches!(from_slice::<Value>(&bytes), Err(UbjError::InvalidHighPrecision("Value"))));
+    assert!(matches!(from_slice::<Number>(&bytes), Err(UbjError::InvalidHighPrecision("Value"))));
+}
+
+#[test]
+fn value_object_keyed_like_high_precision_token() {
+    let value = ubj!([{"$serde_ubj::private::HighPrecision": "1", "x": 1}, high_precision("2")]);
+    let bytes = to_vec(&value).unwrap();
+    assert_eq!(from_slice::<Value>(&bytes).unwrap(), value);
+    assert_eq!(from_value::<Value>(value.clone()).unwrap(), value);
+}
+
+
+// ---------------------------------------------------------------------------------
+//  C O N T A I N E R   values
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn value_array() {
+    assert_value_round_trip_ok!(
+        //[[]   [i]   [7]   [S]   [U]   [1]   [a]   [Z]   []]
+        &[0x5B, 0x69, 0x07, 0x53, 0x55, 0x01, 0x61, 0x5A, 0x5D],
+        Value::Array(vec![Value::Int8(7), Value::from("a"), Value::Null])
+    );
+}
+
+#[test]
+fn value_object_keeps_its_order() {
+    assert_value_round_trip_ok!(
+        &[
+            0x7B,
+            //  [U]   [1]   [z]   [T]
+                0x55, 0x01, 0x7A, 0x54,
+    //                                  [U]   [1]   [a]   [[]   [H]   [U]   [1]   [9]   []]
+                0x55, 0x01, 0x61, 0x5B, 0x48, 0x55, 0x01, 0x39, 0x5D,
+            0x7D
+        ],
+        object(vec![
+            ("z", Value::Bool(true)),
+            ("a", Value::Array(vec![Value::HighPrecision(String::from("9"))])),
+        ])
+    );
+}
+
+#[test]
+fn value_optimized_array() {
+    //           [[]   [$]   [I]   [#]   [i]   [2]   [1]         [2]
+    let bytes = [0x5B, 0x24, 0x49, 0x23, 0x69, 0x02, 0x00, 0x01, 0x00, 0x02];
+    let value: Value = from_slice(&bytes).unwrap();
+    assert_eq!(value, Value::Array(vec![Value::Int16(1), Value::Int16(2)]));
+}
+
+#[test]
+fn value_from_typed_struct_bytes() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct MyFieldsStruct {
+        x: i8,
+        y: bool,
+        z: String,
+    }
+    let bytes = to_vec(&MyFieldsStruct { x: 1, y: true, z: String::from("one") }).unwrap();
+    let value: Value = from_slice(&bytes).unwrap();
+    assert_eq!(
+        value,
+        object(vec![("x", Value::Int8(1)), ("y", Value::Bool(true)), ("z", Value::from("one"))])
+    );
+    let typed: MyFieldsStruct = from_slice(&to_vec(&value).unwrap()).unwrap();
+    assert_eq!(typed.z, "one");
+}
+
+
+// ---------------------------------------------------------------------------------
+//  A C C E S S O R S   and   I N D E X I N G
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn value_accessors() {
+    assert!(Value::Null.is_null());
+    assert_eq!(Value::Bool(true).as_bool(), Some(true));
+    assert_eq!(Value::Int16(-5).as_i64(), Some(-5));
+    assert_eq!(Value::Int16(-5).as_u64(), None);
+    assert_eq!(Value::Uint8(200).as_u64(), Some(200));
+    assert_eq!(Value::HighPrecision(String::from("18446744073709551615")).as_u64(), Some(u64::MAX));
+    assert_eq!(Value::Int32(3).as_f64(), Some(3.0));
+    assert_eq!(Value::Float32(1.5).as_f64(), Some(1.5));
+    assert_eq!(Value::Char('c').as_char(), Some('c'));
+    assert_eq!(Value::from("s").as_str(), Some("s"));
+    assert_eq!(Value::from("s").as_i64(), None);
+    assert_eq!(Value::HighPrecision(String::from("1.5")).as_high_precision(), Some("1.5"));
+    assert_eq!(Value::Array(vec![]).as_array().map(Vec::len), Some(0));
+    assert!(Value::Object(Map::new()).as_object().is_some());
+}
+
+#[test]
+fn value_indexing() {
+    let mut value = object(vec![("a", Value::Array(vec![Value::Int8(1), Value::Int8(2)]))]);
+    assert_eq!(value["a"][1], Value::Int8(2));
+    assert_eq!(value["a"][2], Value::Null);
+    assert_eq!(value["b"], Value::Null);
+    assert_eq!(value[0], Value::Null);
+
+    value["a"][0] = Value::from("x");
+    value["b"] = Value::Bool(false);
+    assert_eq!(value["a"][0], Value::from("x"));
+    assert_eq!(value.as_object().unwrap().keys().collect::<Vec<_>>(), vec!["a", "b"]);
+}
+
+#[test]
+fn value_map() {
+    let mut map = Map::new();
+    assert!(map.insert(String::from("a"), Value::Int8(1)).is_none());
+    assert!(map.insert(String::from("b"), Value::Int8(2)).is_none());
+    assert_eq!(map.insert(String::from("a"), Value::Int8(3)), Some(Value::Int8(1)));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(map["a"], Value::Int8(3));
+    assert_eq!(map.remove("a"), Some(Value::Int8(3)));
+    assert!(!map.contains_key("a"));
+    assert_eq!(map["b"], Value::Int8(2));
+    *map.entry("c") = Value::Int8(4);
+    assert_eq!(map.keys().collect::<Vec<_>>(), vec!["b", "c"]);
+    assert_eq!(map["c"], Value::Int8(4));
+}
+
+#[test]
+fn value_map_of_many_keys() {
+    let entries: Map = (0..200_000).map(|i| (i.to_string(), Value::Int32(i))).collect();
+    let bytes = to_vec(&Value::Object(entries)).unwrap();
+    let value: Value = from_slice(&bytes).unwrap();
+    let map = value.as_object().unwrap();
+    assert_eq!(map.len(), 200_000);
+    assert_eq!(map["199999"], Value::Int32(199_999));
+    assert_eq!(map.keys().nth(1).unwrap(), "1");
+}
+
+
+// ---------------------------------------------------------------------------------
+//  J S O N   text
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn value_display_as_json() {
+    let value = object(vec![
+        ("null", Value::Null),
+        ("ints", Value::Array(vec![Value::Int8(-1), Value::Uint8(2), Value::Int64(3)])),
+        ("floats", Value::Array(vec![Value::Float64(1.5), Value::Float32(f32::NAN)])),
+        ("text", Value::from("a \"quoted\"\n\u{1}")),
+        ("char", Value::Char('c')),
+        ("big", Value::HighPrecision(String::from("12345678901234567890.5"))),
+    ]);
+    assert_eq!(
+        value.to_string(),
+        r#"{"null":null,"ints":[-1,2,3],"floats":[1.5,null],"text":"a \"quoted\"\n\u0001","char":"c","big":12345678901234567890.5}"#
+    );
+}
+
+
+// ---------------------------------------------------------------------------------
+//  T O   and   F R O M   typed values
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn to_from_value_scalars() {
+    assert_to_from_value_ok!((), ());
+    assert_to_from_value_ok!(bool, true);
+    assert_to_from_value_ok!(i8, 7);
+    assert_to_from_value_ok!(i16, -7);
+    assert_to_from_value_ok!(i32, 200);
+    assert_to_from_value_ok!(i64, -32768);
+    assert_to_from_value_ok!(u16, 65535);
+    assert_to_from_value_ok!(u32, 4294967295);
+    assert_to_from_value_ok!(u64, u64::MAX);
+    assert_to_from_value_ok!(i128, i128::MIN);
+    assert_to_from_value_ok!(f32, 1.5);
+    assert_to_from_value_ok!(f64, -2.5);
+    assert_to_from_value_ok!(char, 'c');
+    assert_to_from_value_ok!(String, String::from("hello"));
+    assert_to_from_value_ok!(Option<i8>, None);
+    assert_to_from_value_ok!(Option<i8>, Some(7));
+}
+
+#[test]
+fn to_from_value_compounds() {
+    use model::*;
+    assert_to_from_value_ok!(Vec<i16>, vec![1, 300, -5]);
+    assert_to_from_value_ok!((i8, String), (1, String::from("a")));
+    assert_to_from_value_ok!(MyUnitStruct, MyUnitStruct);
+    assert_to_from_value_ok!(MyNewtypeStruct, MyNewtypeStruct(7));
+    assert_to_from_value_ok!(MyTupleStruct, MyTupleStruct(1, 2, 3));
+    assert_to_from_value_ok!(MyFieldsStruct, MyFieldsStruct { x: 1, y: true, z: String::from("z") });
+}
+
+#[test]
+fn to_from_value_bytes() {
+    use model::MyBytesWrapper;
+    let typed = MyBytesWrapper { bytes: serde_bytes::ByteBuf::from(vec![1, 2]), byte_buf: vec![3] };
+    // Bytes become arrays of uint8 values, which lose their optimized format though
+    let value = to_value(&typed).unwrap();
+    assert_eq!(value["bytes"], Value::Array(vec![Value::Uint8(1), Value::Uint8(2)]));
+    assert_eq!(value, from_slice::<Value>(&to_vec(&typed).unwrap()).unwrap());
+    assert_eq!(from_value::<MyBytesWrapper>(value).unwrap(), typed);
+}
+
+#[test]
+fn to_from_value_enum_variants() {
+    use model::*;
+    assert_to_from_value_ok!(MyEnum, MyEnum::MyUnitVariant);
+    assert_to_from_value_ok!(MyEnum, MyEnum::MyNewtypeVariant(String::from("a")));
+    assert_to_from_value_ok!(MyEnum, MyEnum::MyTupleVariant(1, 2, 3));
+    assert_to_from_value_ok!(MyEnum, MyEnum::MyStructVariant { x: 1, y: false, z: String::from("z") });
+    assert_to_from_value_ok!(MyUntaggedEnum, MyUntaggedEnum::MyFieldsVariant { x: 1, y: true });
+    assert_to_from_value_ok!(MyUntaggedEnum, MyUntaggedEnum::MyListVariant(vec![MyUntaggedEnum::MyNullVariant(())]));
+
+    // Enum variants are objects keyed by the variant name
+    assert_eq!(
+        to_value(&MyEnum::MyNewtypeVariant(String::from("a"))).unwrap(),
+        object(vec![("MyNewtypeVariant", Value::from("a"))])
+    );
+}
+
+#[test]
+fn to_value_err_illegal_key_type() {
+    let map = std::collections::BTreeMap::from([(1_i8, true)]);
+    assert!(matches!(to_value(&map), Err(UbjError::IllegalKeyType("integer"))));
+}
+
+#[test]
+fn from_value_err_type_mismatch() {
+    assert!(from_value::<bool>(Value::Int8(1)).is_err());
+    assert!(from_value::<u8>(Value::Int16(300)).is_err());
+    assert!(from_value::<(i8, i8)>(Value::Array(vec![Value::Int8(1)])).is_err());
+    assert!(from_value::<model::MyEnum>(Value::from("MyUnitVariant")).is_err());
+}
+
+
+// ---------------------------------------------------------------------------------
+//  M A C R O
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn ubj_macro_scalars() {
+    assert_eq!(ubj!(null), Value::Null);
+    assert_eq!(ubj!(true), Value::Bool(true));
+    assert_eq!(ubj!(7), Value::Uint8(7));
+    assert_eq!(ubj!(-7), Value::Int8(-7));
+    assert_eq!(ubj!(300), Value::Int16(300));
+    assert_eq!(ubj!(1.5), Value::Float64(1.5));
+    assert_eq!(ubj!('c'), Value::Char('c'));
+    assert_eq!(ubj!("text"), Value::from("text"));
+}
+
+#[test]
+fn ubj_macro_annotations() {
+    assert_eq!(ubj!(int8(5)), Value::Int8(5));
+    assert_eq!(ubj!(uint8(5)), Value::Uint8(5));
+    assert_eq!(ubj!(int16(5)), Value::Int16(5));
+    assert_eq!(ubj!(int32(5)), Value::Int32(5));
+    assert_eq!(ubj!(int64(5)), Value::Int64(5));
+    assert_eq!(ubj!(float32(1.5)), Value::Float32(1.5));
+    assert_eq!(ubj!(float64(1.5)), Value::Float64(1.5));
+    assert_eq!(ubj!(char('c')), Value::Char('c'));
+    assert_eq!(ubj!(high_precision("1.25")), Value::HighPrecision(String::from("1.25")));
+}
+
+#[test]
+fn ubj_macro_containers() {
+    let name = "x";
+    let value = ubj!({
+        "name": name,
+        "ids": [1, int16(2), [], {}],
+        "nested": {"flag": false, "none": null,},
+        (name.to_uppercase()): ["a", null, {"deep": [int64(-1)]}],
+    });
+    let expected = object(vec![
+        ("name", Value::from("x")),
+        ("ids", Value::Array(vec![Value::Uint8(1), Value::Int16(2), Value::Array(vec![]), Value::Object(Map::new())])),
+        ("nested", object(vec![("flag", Value::Bool(false)), ("none", Value::Null)])),
+        ("X", Value::Array(vec![Value::from("a"), Value::Null, object(vec![("deep", Value::Array(vec![Value::Int64(-1)]))])])),
+    ]);
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn ubj_macro_encodes_annotated_widths() {
+    assert_eq!(
+        to_vec(&ubj!([5, int16(5), int32(5)])).unwrap(),
+        //   [[]   [#]   [U]   [3]   [U]   [5]   [I]   [5]         [l]   [5]
+        vec![0x5B, 0x23, 0x55, 0x03, 0x55, 0x05, 0x49, 0x00, 0x05, 0x6C, 0x00, 0x00, 0x00, 0x05]
+    );
+}
+
+#[test]
+fn ubj_macro_interpolates_serializable_expressions() {
+    use model::MyFieldsStruct;
+    let record = MyFieldsStruct { x: 1, y: true, z: String::from("z") };
+    let ids = vec![1_u16, 2_u16];
+    assert_eq!(
+        ubj!({"record": record, "ids": ids}),
+        object(vec![
+            ("record", object(vec![("x", Value::Int8(1)), ("y", Value::Bool(true)), ("z", Value::from("z"))])),
+            ("ids", Value::Array(vec![Value::Uint8(1), Value::Uint8(2)])),
+        ])
+    );
+}
+
+// ---------------------------------------------------------------------------------
+//  J S O N   P O I N T E R S
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn value_pointer() {
+    let value = ubj!({"a": [{"b": 7}, "x"], "c/d": true, "e~f": null, "": 1});
+    assert_eq!(value.pointer(""), Some(&value));
+    assert_eq!(value.pointer("/a/0/b"), Some(&Value::Uint8(7)));
+    assert_eq!(value.pointer("/a/1"), Some(&Value::from("x")));
+    assert_eq!(value.pointer("/c~1d"), Some(&Value::Bool(true)));
+    assert_eq!(value.pointer("/e~0f"), Some(&Value::Null));
+    assert_eq!(value.pointer("/"), Some(&Value::Uint8(1)));
+}
+
+#[test]
+fn value_pointer_missing() {
+    let value = ubj!({"a": [{"b": 7}, "x"]});
+    assert_eq!(value.pointer("/z"), None);
+    assert_eq!(value.pointer("/a/2"), None);
+    assert_eq!(value.pointer("/a/01"), None);
+    assert_eq!(value.pointer("/a/-"), None);
+    assert_eq!(value.pointer("/a/1/b"), None);
+    assert_eq!(value.pointer("a"), None);
+}
+
+#[test]
+fn value_pointer_mut() {
+    let mut value = ubj!({"a": [{"b": 7}]});
+    *value.pointer_mut("/a/0/b").unwrap() = Value::from("changed");
+    assert_eq!(value, ubj!({"a": [{"b": "changed"}]}));
+    assert!(value.pointer_mut("/a/1").is_none());
+}
+
+// ---------------------------------------------------------------------------------
+//  M E R G E   P A T C H E S
+// ---------------------------------------------------------------------------------
+
+#[test]
+fn value_merge_patch() {
+    let mut value = ubj!({"a": "b", "c": {"d": "e", "f": "g"}, "h": [1, 2]});
+    value.merge_patch(&ubj!({"a": "z", "c": {"f": null}, "h": [3], "i": {"j": null, "k": 1}}));
+    assert_eq!(value, ubj!({"a": "z", "c": {"d": "e"}, "h": [3], "i": {"k": 1}}));
+}
+
+#[test]
+fn value_merge_patch_replaces_non_objects() {
+    let mut value = ubj!([1, 2]);
+    value.merge_patch(&ubj!({"a": 1}));
+    assert_eq!(value, ubj!({"a": 1}));
+
+    let mut value = ubj!({"a": 1});
+    value.merge_patch(&ubj!("text"));
+    assert_eq!(value, ubj!("text"));
+
+    let mut value = ubj!({"a": 1});
+    value.merge_patch(&ubj!({}));
+    assert_eq!(value, ubj!({"a": 1}));
+}
+
+#[test]
+fn value_merge_diff() {
+    let source = ubj!({"a": "b", "c": {"d": "e", "f": "g"}, "h": [1, 2], "x": 1});
+    let target = ubj!({"a": "z", "c": {"d": "e"}, "h": [1, 2], "x": int16(1), "i": {"k": 1}});
+    let patch = source.merge_diff(&target);
+    assert_eq!(patch, ubj!({"a": "z", "c": {"f": null}, "x": int16(1), "i": {"k": 1}}));
+
+    let mut patched = source.clone();
+    patched.merge_patch(&patch);
+    assert_eq!(patched, target);
+}
+
+#[test]
+fn value_merge_diff_of_equal_values() {
+    let value = ubj!({"a": [1, {"b": null}]});
+    assert_eq!(value.merge_diff(&value), ubj!({}));
+    assert_eq!(ubj!([1]).merge_diff(&ubj!([1])), ubj!([1]));
+}
+
+// ---------------------------------------------------------------------------------
+//  N U M B E R S   keeping their markers
+// ---------------------------------------------------------------------------------
+
+macro_rules! assert_number_round_trip_ok {
+    ($bytes:expr, $marker:expr) => {
+        let bytes: &[u8] = $bytes;
+        let number: Number = from_slice(bytes).unwrap();
+        assert_eq!(number.marker(), $marker);
+        assert_eq!(to_vec(&number).unwrap().as_slice(), bytes);
+    };
+}
+
+#[test]
+fn number_round_trip_keeps_markers() {
+    //                            [i]   [1]
+    assert_number_round_trip_ok!(&[0x69, 0x01], 'i');
+    //                            [U]   [1]
+    assert_number_round_trip_ok!(&[0x55, 0x01], 'U');
+    //                            [I]   [1]
+    assert_number_round_trip_ok!(&[0x49, 0x00, 0x01], 'I');
+    //                            [l]   [1]
+    assert_number_round_trip_ok!(&[0x6C, 0x00, 0x00, 0x00, 0x01], 'l');
+    //                            [L]   [1]
+    assert_number_round_trip_ok!(&[0x4C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01], 'L');
+    //                            [d]   [1.5]
+    assert_number_round_trip_ok!(&[0x64, 0x3F, 0xC0, 0x00, 0x00], 'd');
+    //                            [D]   [1.5]
+    assert_number_round_trip_ok!(&[0x44, 0x3F, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], 'D');
+    //                            [H]   [U]   [4]   [1]   [.]   [2]   [5]
+    assert_number_round_trip_ok!(&[0x48, 0x55, 0x04, 0x31, 0x2E, 0x32, 0x35], 'H');
+}
+
+#[test]
+fn number_fields_keep_their_markers() {
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Reading {
+        id: Number,
+        level: Number,
+    }
+    let bytes = [
+        0x7B,
+        //  [U]   [2]   [i]   [d]   [L]   [7]
+            0x55, 0x02, 0x69, 0x64, 0x4C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07,
+        //  [U]   [5]   [l]   [e]   [v]   [e]   [l]   [I]   [-1]
+            0x55, 0x05, 0x6C, 0x65, 0x76, 0x65, 0x6C, 0x49, 0xFF, 0xFF,
+        0x7D,
+    ];
+    let reading: Reading = from_slice(&bytes).unwrap();
+    assert_eq!(reading, Reading { id: Number::from(7_i64), level: Number::from(-1_i16) });
+    assert_eq!(to_plain_vec(&reading), bytes);
+}
+
+#[test]
+fn number_accessors() {
+    assert_eq!(Number::from(-1_i16).as_i64(), Some(-1));
+    assert_eq!(Number::from(-1_i16).as_u64(), None);
+    assert_eq!(Number::from(200_u8).as_f64(), Some(200.0));
+    assert_eq!(Number::from(1.5_f32).as_i64(), None);
+    assert!(Number::from(1_i32).is_integer());
+    assert!(!Number::from(1.5_f64).is_integer());
+    assert_eq!(Number::high_precision("18446744073709551616").as_u64(), None);
+    assert_eq!(Number::high_precision("1.25").as_high_precision(), Some("1.25"));
+    assert_eq!(Number::high_precision("1.25").to_string(), "1.25");
+    assert_eq!(Number::from(f64::NAN).to_string(), "null");
+}
+
+#[test]
+fn number_to_from_value() {
+    assert_eq!(Value::from(Number::from(5_i32)), Value::Int32(5));
+    assert_eq!(Value::Int16(5).as_number(), Some(Number::from(5_i16)));
+    assert_eq!(Value::from("5").as_number(), None);
+}
+
+#[test]
+fn number_err_not_a_number() {
+    //           [S]   [U]   [1]   [5]
+    let bytes = [0x53, 0x55, 0x01, 0x35];
+    assert!(matches!(from_slice::<Number>(&bytes), Err(other_err!())));
+    // The elements of an array are not even read
+    //           [[]   [#]   [L]   [68719476736]                                   [Z]
+    let bytes = [0x5B, 0x23, 0x4C, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x5A];
+    assert!(matches!(from_slice::<Number>(&bytes), Err(other_err!())));
+    assert!(matches!(from_value::<Number>(ubj!([1, 2])), Err(other_err!())));
+    assert_eq!(from_value::<Number>(ubj!(high_precision("1e400"))).unwrap(), Number::high_precision("1e400"));
+}